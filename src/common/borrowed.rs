@@ -0,0 +1,101 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, Visibility};
+
+use super::WrappedVariant;
+
+/// Generates the `{Enum}Ref<'a>` and `{Enum}Mut<'a>` companion enums holding a reference to the
+/// payload of each variant, `as_ref`/`as_mut` to create them and `cloned`/`to_owned` to go back.
+///
+/// `cloned` is bounded by `for<'c> Payload: Clone` rather than `Payload: Clone`, so that it is
+/// simply unavailable, instead of a compile error, when some payload is not [`Clone`]. Payloads
+/// of variants with `#[cfg(...)]` are left out of the bounds, as they may not exist.
+pub fn generate_borrowed(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+) -> TokenStream {
+    let ref_ident = format_ident!("{ident}Ref");
+    let mut_ident = format_ident!("{ident}Mut");
+    let ref_doc = format!("Borrowed view of [`{ident}`], see [`{ident}::as_ref`].");
+    let mut_doc = format!("Mutably borrowed view of [`{ident}`], see [`{ident}::as_mut`].");
+
+    let ids: Vec<_> = variants.iter().map(|variant| &variant.id).collect();
+    let tys: Vec<_> = variants.iter().map(|variant| &variant.ty).collect();
+    let cfgs: Vec<_> = variants.iter().map(WrappedVariant::cfg).collect();
+    let gets = variants.iter().map(|variant| variant.get(quote!(value)));
+    let get_muts = variants
+        .iter()
+        .map(|variant| variant.get_mut(quote!(value)));
+    let ref_clones = variants
+        .iter()
+        .map(|variant| variant.store(quote!(::core::clone::Clone::clone(value))));
+    let mut_clones = variants
+        .iter()
+        .map(|variant| variant.store(quote!(::core::clone::Clone::clone(&**value))));
+    let bounded_tys = tys
+        .iter()
+        .zip(&cfgs)
+        .filter(|(_, cfg)| cfg.is_empty())
+        .map(|(ty, _)| ty);
+    let clone_bounds = quote! {
+        where #(for<'c> #bounded_tys: ::core::clone::Clone,)*
+    };
+
+    quote! {
+        #[doc = #ref_doc]
+        #[derive(Clone, Copy)]
+        #vis enum #ref_ident<'a> {
+            #(#cfgs #ids(&'a #tys),)*
+        }
+        #[doc = #mut_doc]
+        #vis enum #mut_ident<'a> {
+            #(#cfgs #ids(&'a mut #tys),)*
+        }
+
+        impl #ident {
+            #vis fn as_ref(&self) -> #ref_ident<'_> {
+                match *self {
+                    #(#cfgs #ident::#ids(ref value) => #ref_ident::#ids(#gets),)*
+                }
+            }
+            #vis fn as_mut(&mut self) -> #mut_ident<'_> {
+                match *self {
+                    #(#cfgs #ident::#ids(ref mut value) => #mut_ident::#ids(#get_muts),)*
+                }
+            }
+        }
+
+        impl<'a> ::core::convert::From<&'a #ident> for #ref_ident<'a> {
+            fn from(value: &'a #ident) -> Self {
+                value.as_ref()
+            }
+        }
+        impl<'a> ::core::convert::From<&'a mut #ident> for #mut_ident<'a> {
+            fn from(value: &'a mut #ident) -> Self {
+                value.as_mut()
+            }
+        }
+
+        impl<'a> #ref_ident<'a> {
+            #vis fn cloned(&self) -> #ident #clone_bounds {
+                match *self {
+                    #(#cfgs #ref_ident::#ids(value) => #ident::#ids(#ref_clones),)*
+                }
+            }
+            #vis fn to_owned(&self) -> #ident #clone_bounds {
+                self.cloned()
+            }
+        }
+        impl<'a> #mut_ident<'a> {
+            #vis fn cloned(&self) -> #ident #clone_bounds {
+                match *self {
+                    #(#cfgs #mut_ident::#ids(ref value) => #ident::#ids(#mut_clones),)*
+                }
+            }
+            #vis fn to_owned(&self) -> #ident #clone_bounds {
+                self.cloned()
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use proc_macro2::{Ident, Span};
+use syn::ext::IdentExt;
+
+/// Converts a `PascalCase` identifier into `snake_case`, keeping acronyms together
+/// (`HTTPRequest` becomes `http_request`).
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let boundary = match prev {
+                None | Some('_') => false,
+                Some(prev) => {
+                    prev.is_lowercase()
+                        || prev.is_ascii_digit()
+                        || (prev.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+                }
+            };
+            if boundary {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Creates an identifier from `name`, escaping it as a raw identifier when it collides with a keyword.
+pub fn ident_of(name: &str, span: Span) -> Ident {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        Ident::new(name, span)
+    } else if matches!(name, "self" | "super" | "crate" | "Self" | "_") {
+        Ident::new(&format!("{name}_"), span)
+    } else {
+        Ident::new_raw(name, span)
+    }
+}
+
+/// The `snake_case` form of a variant identifier, used for every generated per-variant method.
+pub fn snake_ident(id: &Ident) -> Ident {
+    ident_of(&snake_case(&id.unraw().to_string()), id.span())
+}
+
+/// Converts a `PascalCase` identifier into `kebab-case`.
+pub fn kebab_case(name: &str) -> String {
+    snake_case(name).replace('_', "-")
+}
+
+/// Converts a `PascalCase` identifier into `SCREAMING_SNAKE_CASE`.
+pub fn screaming_snake_case(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
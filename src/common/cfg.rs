@@ -0,0 +1,82 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{Attribute, Meta};
+
+/// The `#[cfg(...)]`s of a variant or a field, repeated on everything generated for it so that it
+/// disappears along with it.
+///
+/// `#[cfg_attr(...)]`s only go to the generated struct, as what they expand to rarely makes sense
+/// anywhere else.
+#[derive(Clone, Default)]
+pub struct Cfg(Vec<Attribute>);
+impl Cfg {
+    pub fn of(attrs: &[Attribute]) -> Self {
+        Self(
+            attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("cfg"))
+                .cloned()
+                .collect(),
+        )
+    }
+    /// Whether the item is always enabled.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// The predicate enabling the item, for an item that is not always enabled.
+    fn predicate(&self) -> TokenStream {
+        let predicates: Vec<_> = self
+            .0
+            .iter()
+            .filter_map(|attr| match &attr.meta {
+                Meta::List(list) => Some(&list.tokens),
+                _ => None,
+            })
+            .collect();
+        match predicates.as_slice() {
+            [predicate] => quote!(#predicate),
+            _ => quote!(all(#(#predicates),*)),
+        }
+    }
+}
+impl ToTokens for Cfg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.append_all(&self.0);
+    }
+}
+
+/// A `usize` constant expression counting the enabled items.
+pub fn cfg_count(cfgs: &[Cfg]) -> TokenStream {
+    quote!(<[()]>::len(&[#(#cfgs ()),*]))
+}
+
+/// Every combination of enabled items among `cfgs`, each with the `#[cfg(...)]` selecting it and
+/// whether each item is enabled in it. For positions that cannot hold attributes, like the
+/// elements of a tuple pattern, one arm per combination stands in for a `#[cfg(...)]` per element.
+pub fn cfg_combinations(cfgs: &[Cfg]) -> Vec<(TokenStream, Vec<bool>)> {
+    let optional: Vec<usize> = (0..cfgs.len()).filter(|&i| !cfgs[i].is_empty()).collect();
+    (0..1usize << optional.len())
+        .map(|mask| {
+            let mut enabled = vec![true; cfgs.len()];
+            let conditions: Vec<_> = optional
+                .iter()
+                .enumerate()
+                .map(|(bit, &i)| {
+                    let predicate = cfgs[i].predicate();
+                    if mask & (1 << bit) == 0 {
+                        enabled[i] = false;
+                        quote!(not(#predicate))
+                    } else {
+                        predicate
+                    }
+                })
+                .collect();
+            let cfg = match conditions.as_slice() {
+                [] => quote!(),
+                [condition] => quote!(#[cfg(#condition)]),
+                _ => quote!(#[cfg(all(#(#conditions),*))]),
+            };
+            (cfg, enabled)
+        })
+        .collect()
+}
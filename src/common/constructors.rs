@@ -0,0 +1,84 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Ident, Result, Visibility};
+
+use super::{snake_ident, WrappedVariant};
+
+impl WrappedVariant {
+    /// The name of the constructor of the variant on the enum: the `snake_case` form of its
+    /// `rename`, if any, or of its identifier.
+    pub fn constructor_ident(&self) -> Ident {
+        snake_ident(self.options.rename.as_ref().unwrap_or(&self.id))
+    }
+}
+
+/// The methods generated on the enum by the options shared by both macros.
+pub const VISITOR_METHODS: &[&str] = &["accept", "accept_mut", "accept_owned"];
+pub const FOLD_METHODS: &[&str] = &[
+    "fold",
+    "fold_ref",
+    "fold_mut",
+    "folder",
+    "folder_ref",
+    "folder_mut",
+];
+pub const BORROWED_METHODS: &[&str] = &["as_ref", "as_mut"];
+pub const NAMES_METHODS: &[&str] = &["variant_name"];
+pub const TAG_METHODS: &[&str] = &["tag"];
+pub const ITER_METHODS: &[&str] = &["variant_index"];
+pub const ITER_DEFAULT_METHODS: &[&str] = &["all_default", "next", "prev"];
+
+/// The methods of the enabled options among `options`.
+pub fn enabled_methods(options: &[(bool, &[&str])]) -> Vec<String> {
+    options
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .flat_map(|(_, methods)| methods.iter().map(ToString::to_string))
+        .collect()
+}
+
+/// Generates one constructor per variant on the enum, given the parameters of each and the
+/// expression building the variant from them. `taken` holds the other methods generated on the
+/// enum, a constructor with one of their names is reported on its variant.
+pub fn generate_variant_constructors(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+    constructors: Vec<(TokenStream, TokenStream)>,
+    taken: &[String],
+) -> Result<TokenStream> {
+    let mut names = Vec::new();
+    for variant in variants {
+        let name = variant.constructor_ident();
+        if taken.iter().any(|taken| name == taken) {
+            Err(Error::new_spanned(
+                &variant.id,
+                format!("the constructor `{name}` of this variant conflicts with another method of `{ident}`"),
+            ))?
+        }
+        names.push(name);
+    }
+    let methods =
+        variants
+            .iter()
+            .zip(constructors)
+            .zip(names)
+            .map(|((variant, (params, value)), name)| {
+                let id = &variant.id;
+                let cfg = variant.cfg();
+                let doc = format!("Builds a [`{ident}::{id}`].");
+                quote! {
+                    #cfg
+                    #[doc = #doc]
+                    #[allow(clippy::too_many_arguments)]
+                    #vis fn #name(#params) -> Self {
+                        #value
+                    }
+                }
+            });
+    Ok(quote! {
+        impl #ident {
+            #(#methods)*
+        }
+    })
+}
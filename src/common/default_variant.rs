@@ -0,0 +1,113 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    Attribute, Error, Ident, Meta, MetaList, Path, Result, Token,
+};
+
+use super::{kw, Eq};
+
+/// `default = Variant`
+pub struct DefaultParam {
+    pub default: kw::default,
+    pub value: Eq<Ident>,
+}
+impl Parse for DefaultParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            default: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+impl ToTokens for DefaultParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.default.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+/// Removes `#[default]` from every variant and returns the position of the default variant, given
+/// either by `#[default]` or by `default = Variant`.
+pub fn take_default_variant<'a>(
+    param: Option<&DefaultParam>,
+    variants: impl IntoIterator<Item = (&'a Ident, &'a mut Vec<Attribute>)>,
+) -> Result<Option<usize>> {
+    let mut default = None;
+    let mut error = None;
+    for (index, (id, attrs)) in variants.into_iter().enumerate() {
+        attrs.retain(|attr| {
+            if !matches!(&attr.meta, Meta::Path(path) if path.is_ident("default")) {
+                return true;
+            }
+            if default.replace(index).is_some() || param.is_some() {
+                error.get_or_insert(Error::new_spanned(attr, "multiple default variants"));
+            }
+            false
+        });
+        if param.is_some_and(|param| param.value.value == *id) {
+            default = Some(index);
+        }
+    }
+    if let Some(err) = error {
+        return Err(err);
+    }
+    match param {
+        Some(param) if default.is_none() => Err(Error::new_spanned(
+            &param.value.value,
+            "no variant with this name",
+        )),
+        _ => Ok(default),
+    }
+}
+
+/// Whether a `#[derive(...)]` in `attrs` derives `Default`.
+pub fn derives_default(attrs: &[Attribute]) -> Result<bool> {
+    for attr in attrs {
+        if let Meta::List(MetaList { path, tokens, .. }) = &attr.meta {
+            if path.is_ident("derive")
+                && Punctuated::<Path, Token![,]>::parse_terminated
+                    .parse2(tokens.clone())?
+                    .iter()
+                    .any(is_default)
+            {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Removes `Default` from the `#[derive(...)]`s in `attrs`, since the impl is generated instead.
+pub fn remove_default_derive(attrs: &mut [Attribute]) -> Result<()> {
+    for attr in attrs {
+        if let Meta::List(list) = &mut attr.meta {
+            if list.path.is_ident("derive") {
+                let paths = Punctuated::<Path, Token![,]>::parse_terminated
+                    .parse2(list.tokens.clone())?
+                    .into_iter()
+                    .filter(|path| !is_default(path));
+                list.tokens = quote!(#(#paths),*);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_default(path: &Path) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Default")
+}
+
+/// Generates `impl Default` for the enum, returning `value`.
+pub fn generate_default_impl(ident: &Ident, value: TokenStream) -> TokenStream {
+    quote! {
+        impl ::core::default::Default for #ident {
+            fn default() -> Self {
+                #value
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    parse::{Parse, ParseStream},
+    token, Error, LitStr, Result, Token, Visibility,
+};
+
+use super::{kw, nested_vis, Eq, Parenthesized};
+
+/// `pub`, `pub(...)` or `inherit`
+#[derive(Clone)]
+pub enum FieldVis {
+    Inherit(kw::inherit),
+    Vis(Visibility),
+}
+impl FieldVis {
+    /// The visibility of a field, given the visibility of its struct.
+    ///
+    /// `nested` tells whether the struct is generated one module deeper than the enum, in which
+    /// case an explicit visibility is adjusted like the struct's.
+    pub fn resolve(&self, struct_vis: &Visibility, nested: bool) -> Visibility {
+        match self {
+            FieldVis::Inherit(_) => struct_vis.clone(),
+            FieldVis::Vis(vis) if nested => nested_vis(vis),
+            FieldVis::Vis(vis) => vis.clone(),
+        }
+    }
+}
+impl Parse for FieldVis {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(kw::inherit) {
+            return Ok(FieldVis::Inherit(input.parse()?));
+        }
+        match input.parse()? {
+            Visibility::Inherited => Err(input.error("expected `pub`, `pub(...)` or `inherit`")),
+            vis => Ok(FieldVis::Vis(vis)),
+        }
+    }
+}
+impl ToTokens for FieldVis {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            FieldVis::Inherit(inherit) => inherit.to_tokens(tokens),
+            FieldVis::Vis(vis) => vis.to_tokens(tokens),
+        }
+    }
+}
+
+/// `field_vis(...)` or `field_vis = "..."`
+pub struct FieldVisParam {
+    pub field_vis: kw::field_vis,
+    pub value: FieldVisValue,
+}
+pub enum FieldVisValue {
+    Parenthesized(Parenthesized<FieldVis>),
+    Eq(Eq<LitStr>, FieldVis),
+}
+impl FieldVisParam {
+    pub fn value(&self) -> &FieldVis {
+        match &self.value {
+            FieldVisValue::Parenthesized(parenthesized) => &parenthesized.value,
+            FieldVisValue::Eq(_, vis) => vis,
+        }
+    }
+}
+impl Parse for FieldVisParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let field_vis = input.parse()?;
+        let lookahead = input.lookahead1();
+        let value = if lookahead.peek(token::Paren) {
+            FieldVisValue::Parenthesized(input.parse()?)
+        } else if lookahead.peek(Token![=]) {
+            let eq: Eq<LitStr> = input.parse()?;
+            let vis = eq.value.parse().map_err(|_| {
+                Error::new_spanned(&eq.value, r#"expected "pub", "pub(...)" or "inherit""#)
+            })?;
+            FieldVisValue::Eq(eq, vis)
+        } else {
+            Err(lookahead.error())?
+        };
+        Ok(Self { field_vis, value })
+    }
+}
+impl ToTokens for FieldVisParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.field_vis.to_tokens(tokens);
+        match &self.value {
+            FieldVisValue::Parenthesized(parenthesized) => parenthesized.to_tokens(tokens),
+            FieldVisValue::Eq(eq, _) => eq.to_tokens(tokens),
+        }
+    }
+}
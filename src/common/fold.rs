@@ -0,0 +1,157 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, Ident, Index, Result, Type, Visibility};
+
+use super::{snake_ident, Cfg, WrappedVariant};
+
+/// Generates `fold`, `fold_ref` and `fold_mut`, which take one closure per variant, and the
+/// `{Enum}Fold` typestate builder returned by `folder`, `folder_ref` and `folder_mut`.
+///
+/// The builder has one type parameter per variant, which `#[cfg(...)]` cannot remove, so it is
+/// left out when some variants have one.
+pub fn generate_fold(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+) -> Result<TokenStream> {
+    let fold = format_ident!("{ident}Fold");
+    let missing = format_ident!("{ident}FoldMissing");
+
+    let ids: Vec<_> = variants.iter().map(|variant| &variant.id).collect();
+    let args: Vec<_> = ids.iter().map(|id| snake_ident(id)).collect();
+    let tys: Vec<_> = variants.iter().map(|variant| &variant.ty).collect();
+    let cfgs: Vec<_> = variants.iter().map(WrappedVariant::cfg).collect();
+    let gets = variants.iter().map(|variant| variant.get(quote!(value)));
+    let get_muts = variants
+        .iter()
+        .map(|variant| variant.get_mut(quote!(value)));
+    let loads = variants.iter().map(|variant| variant.load(quote!(value)));
+    let fs: Vec<_> = (0..variants.len())
+        .map(|i| format_ident!("__F{i}"))
+        .collect();
+    let indices: Vec<_> = (2..variants.len() + 2).map(Index::from).collect();
+    let missings: Vec<_> = variants.iter().map(|_| &missing).collect();
+    let fold_doc = format!(
+        "Builds a fold over [`{ident}`] one arm at a time, `finish` only exists once every arm is given."
+    );
+    let missing_doc = format!("An arm of [`{fold}`] that has not been given yet.");
+
+    let fold_methods = quote! {
+        #vis fn fold<R>(self, #(#cfgs #args: impl ::core::ops::FnOnce(#tys) -> R),*) -> R {
+            match self {
+                #(#cfgs #ident::#ids(value) => #args(#loads),)*
+            }
+        }
+        #vis fn fold_ref<'a, R>(&'a self, #(#cfgs #args: impl ::core::ops::FnOnce(&'a #tys) -> R),*) -> R {
+            match *self {
+                #(#cfgs #ident::#ids(ref value) => #args(#gets),)*
+            }
+        }
+        #vis fn fold_mut<'a, R>(&'a mut self, #(#cfgs #args: impl ::core::ops::FnOnce(&'a mut #tys) -> R),*) -> R {
+            match *self {
+                #(#cfgs #ident::#ids(ref mut value) => #args(#get_muts),)*
+            }
+        }
+    };
+
+    let flavor = |lifetime: TokenStream,
+                  target: TokenStream,
+                  param_ty: &dyn Fn(&Type) -> TokenStream,
+                  method: Ident| {
+        let param_tys: Vec<_> = tys.iter().map(|ty| param_ty(ty)).collect();
+        let setters = args.iter().zip(&param_tys).enumerate().map(|(i, (arg, param_ty))| {
+            let ty_params = fs
+                .iter()
+                .enumerate()
+                .map(|(j, f)| if i == j { quote!(__F) } else { quote!(#f) });
+            let values = indices.iter().enumerate().map(|(j, index)| {
+                if i == j {
+                    quote!(#arg)
+                } else {
+                    quote!(self.#index)
+                }
+            });
+            quote! {
+                #vis fn #arg<__F: ::core::ops::FnOnce(#param_ty) -> __R>(self, #arg: __F) -> #fold<#target, __R, #(#ty_params),*> {
+                    #fold(self.0, self.1, #(#values),*)
+                }
+            }
+        });
+        quote! {
+            impl<#lifetime __R, #(#fs),*> #fold<#target, __R, #(#fs),*> {
+                #(#setters)*
+                #vis fn finish(self) -> __R
+                where
+                    #(#fs: ::core::ops::FnOnce(#param_tys) -> __R,)*
+                {
+                    self.0.#method(#(self.#indices),*)
+                }
+            }
+        }
+    };
+    if !cfgs.iter().all(Cfg::is_empty) {
+        return Ok(quote! {
+            #[allow(clippy::too_many_arguments)]
+            impl #ident {
+                #fold_methods
+            }
+        });
+    }
+    // The setters of the builder are named after the variants, next to its `finish`.
+    if let Some(id) = ids
+        .iter()
+        .zip(&args)
+        .find_map(|(id, arg)| (arg == "finish").then_some(id))
+    {
+        Err(Error::new_spanned(
+            id,
+            format!("the setter `finish` of this variant conflicts with `finish` of `{fold}`"),
+        ))?
+    }
+
+    let owned = flavor(
+        quote!(),
+        quote!(#ident),
+        &|ty| quote!(#ty),
+        format_ident!("fold"),
+    );
+    let by_ref = flavor(
+        quote!('a,),
+        quote!(&'a #ident),
+        &|ty| quote!(&'a #ty),
+        format_ident!("fold_ref"),
+    );
+    let by_mut = flavor(
+        quote!('a,),
+        quote!(&'a mut #ident),
+        &|ty| quote!(&'a mut #ty),
+        format_ident!("fold_mut"),
+    );
+
+    Ok(quote! {
+        #[allow(clippy::too_many_arguments)]
+        impl #ident {
+            #fold_methods
+            #vis fn folder<R>(self) -> #fold<Self, R, #(#missings),*> {
+                #fold(self, ::core::marker::PhantomData, #(#missings),*)
+            }
+            #vis fn folder_ref<R>(&self) -> #fold<&Self, R, #(#missings),*> {
+                #fold(self, ::core::marker::PhantomData, #(#missings),*)
+            }
+            #vis fn folder_mut<R>(&mut self) -> #fold<&mut Self, R, #(#missings),*> {
+                #fold(self, ::core::marker::PhantomData, #(#missings),*)
+            }
+        }
+
+        #[doc = #fold_doc]
+        #[must_use]
+        #vis struct #fold<__T, __R, #(#fs),*>(__T, ::core::marker::PhantomData<fn() -> __R>, #(#fs),*);
+
+        #[doc = #missing_doc]
+        #vis struct #missing;
+
+        #owned
+        #by_ref
+        #by_mut
+    })
+}
@@ -0,0 +1,95 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    token, Ident, Result, Visibility,
+};
+
+use super::{cfg_count, kw, Parenthesized, WrappedVariant};
+
+/// `iter` or `iter(default)`
+pub struct IterParam {
+    pub iter: kw::iter,
+    pub default: Option<Parenthesized<kw::default>>,
+}
+impl IterParam {
+    /// Whether `all_default`, `next` and `prev` are generated.
+    pub fn has_default(&self) -> bool {
+        self.default.is_some()
+    }
+}
+impl Parse for IterParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            iter: input.parse()?,
+            default: if input.peek(token::Paren) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+        })
+    }
+}
+impl ToTokens for IterParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.iter.to_tokens(tokens);
+        self.default.to_tokens(tokens);
+    }
+}
+
+/// Generates `VARIANT_COUNT` and `variant_index` on the enum.
+///
+/// Given `constructors`, an expression building each variant, also generates `all_default`, `next`
+/// and `prev`.
+///
+/// The index of a variant only counts the variants before it whose `#[cfg(...)]` is enabled.
+pub fn generate_iter(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+    constructors: Option<&[TokenStream]>,
+) -> TokenStream {
+    let ids = variants.iter().map(|variant| &variant.id);
+    let cfgs: Vec<_> = variants.iter().map(WrappedVariant::cfg).collect();
+    let count = cfg_count(&cfgs);
+    let indices: Vec<_> = (0..variants.len()).map(|i| cfg_count(&cfgs[..i])).collect();
+
+    // `next` and `prev` would divide by zero on an empty enum.
+    let cycling = constructors
+        .filter(|_| !variants.is_empty())
+        .map(|constructors| {
+            quote! {
+                #vis fn all_default() -> impl ::core::iter::Iterator<Item = Self> {
+                    ::core::iter::IntoIterator::into_iter([#(#cfgs #constructors),*])
+                }
+                // `VARIANT_COUNT` is 1 when a single variant is enabled, which clippy denies as a
+                // modulo.
+                #[allow(clippy::modulo_one)]
+                #vis fn next(&self) -> Self {
+                    match (self.variant_index() + 1) % Self::VARIANT_COUNT {
+                        #(#cfgs index if index == #indices => #constructors,)*
+                        _ => ::core::unreachable!(),
+                    }
+                }
+                #[allow(clippy::modulo_one)]
+                #vis fn prev(&self) -> Self {
+                    match (self.variant_index() + Self::VARIANT_COUNT - 1) % Self::VARIANT_COUNT {
+                        #(#cfgs index if index == #indices => #constructors,)*
+                        _ => ::core::unreachable!(),
+                    }
+                }
+            }
+        });
+
+    quote! {
+        impl #ident {
+            #vis const VARIANT_COUNT: usize = #count;
+            #vis fn variant_index(&self) -> usize {
+                match *self {
+                    #(#cfgs #ident::#ids { .. } => #indices,)*
+                }
+            }
+            #cycling
+        }
+    }
+}
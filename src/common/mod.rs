@@ -193,32 +193,43 @@ pub struct WrappedVariant {
     pub attrs: Vec<Attribute>,
     pub id: Ident,
     pub ty: Type,
+    pub options: VariantOptions,
+    pub storage: Storage,
+}
+
+impl WrappedVariant {
+    pub fn cfg(&self) -> Cfg {
+        Cfg::of(&self.attrs)
+    }
 }
 
 impl ToTokens for WrappedVariant {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.append_all(&self.attrs);
         self.id.to_tokens(tokens);
-        tokens.append(Group::new(
-            Delimiter::Parenthesis,
-            self.ty.to_token_stream(),
-        ));
+        tokens.append(Group::new(Delimiter::Parenthesis, self.stored_ty()));
     }
 }
 
-pub fn generate_conversion_impl(ident: &Ident, id: &Ident, ty: &Type) -> TokenStream {
+pub fn generate_conversion_impl(ident: &Ident, variant: &WrappedVariant) -> TokenStream {
+    let WrappedVariant { id, ty, .. } = variant;
+    let cfg = variant.cfg();
+    let store = variant.store(quote!(value));
+    let load = variant.load(quote!(value));
     quote! {
+        #cfg
         impl ::core::convert::From<#ty> for #ident {
             fn from(value: #ty) -> Self {
-                #ident::#id(value)
+                #ident::#id(#store)
             }
         }
 
+        #cfg
         impl ::core::convert::TryFrom<#ident> for #ty {
             type Error = #ident;
             fn try_from(value: #ident) -> ::core::result::Result<Self, Self::Error> {
                 if let #ident::#id(value) = value {
-                    ::core::result::Result::Ok(value)
+                    ::core::result::Result::Ok(#load)
                 } else {
                     ::core::result::Result::Err(value)
                 }
@@ -241,6 +252,54 @@ mod tag {
     }
 }
 
+pub use case::*;
+mod case;
+
+pub use visitor::*;
+mod visitor;
+
+pub use fold::*;
+mod fold;
+
+pub use borrowed::*;
+mod borrowed;
+
+pub use variant_options::*;
+mod variant_options;
+
+pub use names::*;
+mod names;
+
+pub use iter::*;
+mod iter;
+
+pub use template::*;
+mod template;
+
+pub use module::*;
+mod module;
+
+pub use field_vis::*;
+mod field_vis;
+
+pub use cfg::*;
+mod cfg;
+
+pub use default_variant::*;
+mod default_variant;
+
+pub use storage::*;
+mod storage;
+
+pub use size::*;
+mod size;
+
+pub use require::*;
+mod require;
+
+pub use constructors::*;
+mod constructors;
+
 pub struct Visitor<T>(Result<T>);
 impl<T: Default> Default for Visitor<T> {
     fn default() -> Self {
@@ -257,6 +316,37 @@ pub mod kw {
     custom_keyword!(simplify);
     custom_keyword!(tag);
     custom_keyword!(generate);
+    custom_keyword!(visitor);
+    custom_keyword!(default);
+    custom_keyword!(fold);
+    custom_keyword!(borrowed);
+    custom_keyword!(views);
+    custom_keyword!(name);
+    custom_keyword!(names);
+    custom_keyword!(rename_all);
+    custom_keyword!(from_str);
+    custom_keyword!(reflect);
+    custom_keyword!(field_access);
+    custom_keyword!(iter);
+    custom_keyword!(rename);
+    custom_keyword!(module);
+    custom_keyword!(field_vis);
+    custom_keyword!(vis);
+    custom_keyword!(inherit);
+    custom_keyword!(no_inherit_derive);
+    custom_keyword!(inherit_derive);
+    custom_keyword!(existing);
+    custom_keyword!(wrap_existing);
+    custom_keyword!(boxed);
+    custom_keyword!(box_above);
+    custom_keyword!(max_size);
+    custom_keyword!(size_report);
+    custom_keyword!(require);
+    custom_keyword!(new);
+    custom_keyword!(into);
+    custom_keyword!(tuple);
+    custom_keyword!(constructors);
+    custom_keyword!(builder);
 }
 
 pub struct Eq<T = Lit> {
@@ -0,0 +1,94 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    token, Ident, Path, PathSegment, Result, Token, VisRestricted, Visibility,
+};
+
+use super::{kw, Eq, Parenthesized};
+
+/// `module = name` or `module(name)`
+pub struct Module {
+    pub module: kw::module,
+    pub name: ModuleName,
+}
+pub enum ModuleName {
+    Eq(Eq<Ident>),
+    Parenthesized(Parenthesized<Ident>),
+}
+impl Module {
+    pub fn ident(&self) -> &Ident {
+        match &self.name {
+            ModuleName::Eq(eq) => &eq.value,
+            ModuleName::Parenthesized(parenthesized) => &parenthesized.value,
+        }
+    }
+}
+impl Parse for Module {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let module = input.parse()?;
+        let lookahead = input.lookahead1();
+        let name = if lookahead.peek(Token![=]) {
+            ModuleName::Eq(input.parse()?)
+        } else if lookahead.peek(token::Paren) {
+            ModuleName::Parenthesized(input.parse()?)
+        } else {
+            Err(lookahead.error())?
+        };
+        Ok(Self { module, name })
+    }
+}
+impl ToTokens for Module {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.module.to_tokens(tokens);
+        match &self.name {
+            ModuleName::Eq(eq) => eq.to_tokens(tokens),
+            ModuleName::Parenthesized(parenthesized) => parenthesized.to_tokens(tokens),
+        }
+    }
+}
+
+/// The visibility that, given to an item one module deeper, makes it visible from the same places
+/// as `vis`.
+pub fn nested_vis(vis: &Visibility) -> Visibility {
+    let restricted = |path: Path| {
+        Visibility::Restricted(VisRestricted {
+            pub_token: Default::default(),
+            paren_token: Default::default(),
+            in_token: (!path.is_ident("super")).then(Default::default),
+            path: Box::new(path),
+        })
+    };
+    let super_segment = || PathSegment::from(Ident::new("super", Span::call_site()));
+    match vis {
+        Visibility::Public(_) => vis.clone(),
+        Visibility::Inherited => restricted(Path::from(super_segment())),
+        Visibility::Restricted(VisRestricted { path, .. }) => {
+            let first = path
+                .segments
+                .first()
+                .map(|segment| segment.ident.to_string());
+            if path.leading_colon.is_some() || first.as_deref() == Some("crate") {
+                return vis.clone();
+            }
+            let mut nested = (**path).clone();
+            if first.as_deref() == Some("self") {
+                nested.segments[0] = super_segment();
+            } else {
+                nested.segments.insert(0, super_segment());
+            }
+            restricted(nested)
+        }
+    }
+}
+
+/// Wraps `items` in `vis mod name { use super::*; ... }`.
+pub fn generate_module(vis: &Visibility, name: &Ident, items: TokenStream) -> TokenStream {
+    quote! {
+        #vis mod #name {
+            #[allow(unused_imports)]
+            use super::*;
+            #items
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    Error, Ident, LitStr, Result, Visibility,
+};
+
+use super::{cfg_count, kebab_case, kw, screaming_snake_case, snake_case, Eq, WrappedVariant};
+
+pub enum RenameAllValue {
+    Snake(LitStr),
+    Kebab(LitStr),
+    ScreamingSnake(LitStr),
+}
+impl RenameAllValue {
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            RenameAllValue::Snake(_) => snake_case(name),
+            RenameAllValue::Kebab(_) => kebab_case(name),
+            RenameAllValue::ScreamingSnake(_) => screaming_snake_case(name),
+        }
+    }
+}
+impl Parse for RenameAllValue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let str: LitStr = input.parse()?;
+        match str.value().as_str() {
+            "snake_case" => Ok(RenameAllValue::Snake(str)),
+            "kebab-case" => Ok(RenameAllValue::Kebab(str)),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameAllValue::ScreamingSnake(str)),
+            _ => Err(Error::new_spanned(
+                str,
+                r#"expected one of: "snake_case", "kebab-case", "SCREAMING_SNAKE_CASE""#,
+            )),
+        }
+    }
+}
+impl ToTokens for RenameAllValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            RenameAllValue::Snake(str)
+            | RenameAllValue::Kebab(str)
+            | RenameAllValue::ScreamingSnake(str) => str.to_tokens(tokens),
+        }
+    }
+}
+/// `rename_all = "..."`
+pub struct RenameAll {
+    pub rename_all: kw::rename_all,
+    pub value: Eq<RenameAllValue>,
+}
+impl Parse for RenameAll {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            rename_all: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+impl ToTokens for RenameAll {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.rename_all.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+/// The string name of each variant: its `#[attribute(name = "...")]` if any, otherwise its
+/// identifier converted by `rename_all`.
+pub fn variant_names(variants: &[WrappedVariant], rename_all: Option<&RenameAll>) -> Vec<String> {
+    variants
+        .iter()
+        .map(|variant| match &variant.options.name {
+            Some(name) => name.value(),
+            None => {
+                let id = variant.id.unraw().to_string();
+                match rename_all {
+                    Some(rename_all) => rename_all.value.value.apply(&id),
+                    None => id,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Generates `variant_name` on the enum and `NAME` on each payload type, except those marked in
+/// `existing`.
+pub fn generate_names(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+    existing: &[bool],
+    names: &[String],
+) -> TokenStream {
+    let ids = variants.iter().map(|variant| &variant.id);
+    let cfgs: Vec<_> = variants.iter().map(WrappedVariant::cfg).collect();
+    let consts = variants
+        .iter()
+        .zip(names)
+        .zip(existing)
+        .filter(|(_, existing)| !**existing)
+        .map(|((variant @ WrappedVariant { ty, .. }, name), _)| {
+            let cfg = variant.cfg();
+            quote! {
+                #cfg
+                impl #ty {
+                    #vis const NAME: &'static str = #name;
+                }
+            }
+        });
+    quote! {
+        impl #ident {
+            #vis fn variant_name(&self) -> &'static str {
+                match *self {
+                    #(#cfgs #ident::#ids { .. } => #names,)*
+                }
+            }
+        }
+        #(#consts)*
+    }
+}
+
+/// Generates the `Parse{Enum}Error` returned by the generated [`FromStr`](core::str::FromStr) impls.
+pub fn generate_parse_error(vis: &Visibility, ident: &Ident) -> TokenStream {
+    let error = format_ident!("Parse{ident}Error");
+    let doc = format!("The error returned when parsing an unknown [`{ident}`] variant name.");
+    let message = format!("unknown `{ident}` variant name: {{:?}}");
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis struct #error(::std::string::String);
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::write!(f, #message, self.0)
+            }
+        }
+        impl ::std::error::Error for #error {}
+    }
+}
+
+/// Generates the fieldless `{Enum}Tag` enum, `tag` on the enum and the tag's `name`,
+/// [`Display`](core::fmt::Display) and [`FromStr`](core::str::FromStr).
+pub fn generate_tag(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+    names: &[String],
+) -> TokenStream {
+    let tag = format_ident!("{ident}Tag");
+    let error = format_ident!("Parse{ident}Error");
+    let doc = format!("The variants of [`{ident}`] without their payload.");
+    let ids: Vec<_> = variants.iter().map(|variant| &variant.id).collect();
+    let cfgs: Vec<_> = variants.iter().map(WrappedVariant::cfg).collect();
+    let count = cfg_count(&cfgs);
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #vis enum #tag {
+            #(#cfgs #ids,)*
+        }
+        impl #ident {
+            #vis fn tag(&self) -> #tag {
+                match *self {
+                    #(#cfgs #ident::#ids { .. } => #tag::#ids,)*
+                }
+            }
+        }
+        impl ::core::convert::From<&#ident> for #tag {
+            fn from(value: &#ident) -> Self {
+                value.tag()
+            }
+        }
+        impl #tag {
+            /// Every tag, in declaration order.
+            #vis const ALL: [#tag; #count] = [#(#cfgs #tag::#ids),*];
+            #vis fn name(self) -> &'static str {
+                match self {
+                    #(#cfgs #tag::#ids => #names,)*
+                }
+            }
+        }
+        impl ::core::fmt::Display for #tag {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+        impl ::core::str::FromStr for #tag {
+            type Err = #error;
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#cfgs #names => ::core::result::Result::Ok(#tag::#ids),)*
+                    _ => ::core::result::Result::Err(#error(::std::string::String::from(s))),
+                }
+            }
+        }
+    }
+}
+
+/// Generates a [`FromStr`](core::str::FromStr) for the enum that default-constructs the payload
+/// of the named variant.
+pub fn generate_from_str(
+    ident: &Ident,
+    variants: &[WrappedVariant],
+    names: &[String],
+) -> TokenStream {
+    let error = format_ident!("Parse{ident}Error");
+    let ids = variants.iter().map(|variant| &variant.id);
+    let defaults = variants.iter().map(|variant| {
+        let ty = &variant.ty;
+        variant.store(quote!(<#ty as ::core::default::Default>::default()))
+    });
+    let cfgs = variants.iter().map(WrappedVariant::cfg);
+    quote! {
+        impl ::core::str::FromStr for #ident {
+            type Err = #error;
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                match s {
+                    #(#cfgs #names => ::core::result::Result::Ok(#ident::#ids(#defaults)),)*
+                    _ => ::core::result::Result::Err(#error(::std::string::String::from(s))),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token, Result, Token, TypeParamBound,
+};
+
+use super::{kw, WrappedVariant};
+
+/// `require(Bound0, Bound1, ...)`
+pub struct Require {
+    pub require: kw::require,
+    pub paren_token: token::Paren,
+    pub bounds: Punctuated<TypeParamBound, Token![,]>,
+}
+impl Parse for Require {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            require: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            bounds: Punctuated::parse_terminated(&content)?,
+        })
+    }
+}
+impl ToTokens for Require {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.require.to_tokens(tokens);
+        self.paren_token
+            .surround(tokens, |tokens| self.bounds.to_tokens(tokens));
+    }
+}
+
+/// Generates one static assertion per variant that its payload satisfies every bound of `require`.
+/// Each assertion is spanned to its variant, so that is where an unsatisfied bound is reported.
+pub fn generate_require(variants: &[WrappedVariant], require: &Require) -> TokenStream {
+    let bounds = require.bounds.iter();
+    let assertions = variants.iter().map(|variant| {
+        let ty = &variant.ty;
+        let cfg = variant.cfg();
+        let assertion = quote_spanned!(variant.id.span()=> __require::<#ty>);
+        quote! {
+            #cfg
+            let _ = #assertion;
+        }
+    });
+    quote! {
+        const _: () = {
+            fn __require<T: #(#bounds +)*>() {}
+            #(#assertions)*
+        };
+    }
+}
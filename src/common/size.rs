@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    Ident, LitInt, Result, Visibility,
+};
+
+use super::{kw, Eq, WrappedVariant};
+
+/// `max_size = bytes`
+pub struct MaxSize {
+    pub max_size: kw::max_size,
+    pub value: Eq<LitInt>,
+}
+impl Parse for MaxSize {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let max_size = input.parse()?;
+        let value: Eq<LitInt> = input.parse()?;
+        value.value.base10_parse::<usize>()?;
+        Ok(Self { max_size, value })
+    }
+}
+impl ToTokens for MaxSize {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.max_size.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+/// Generates one const assertion per variant that the payload, as held by the enum, is at most
+/// `max_size` bytes. Each assertion is spanned to its variant, so that is where the error points.
+pub fn generate_size_assertions(
+    ident: &Ident,
+    variants: &[WrappedVariant],
+    max_size: &MaxSize,
+) -> TokenStream {
+    let bytes = &max_size.value.value;
+    let assertions = variants.iter().map(|variant| {
+        let id = &variant.id;
+        let stored_ty = variant.stored_ty();
+        let cfg = variant.cfg();
+        let message = format!(
+            "the payload of `{ident}::{id}` is larger than `max_size = {bytes}`, consider `#[attribute(boxed)]`"
+        );
+        quote_spanned! {id.span()=>
+            #cfg
+            const _: () = ::core::assert!(::core::mem::size_of::<#stored_ty>() <= #bytes, #message);
+        }
+    });
+    quote! {
+        #(#assertions)*
+    }
+}
+
+/// Generates `SIZE_REPORT` on the enum, the name and size in bytes of each payload as held by the
+/// enum.
+pub fn generate_size_report(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+) -> TokenStream {
+    let entries = variants.iter().map(|variant| {
+        let name = variant.id.unraw().to_string();
+        let stored_ty = variant.stored_ty();
+        let cfg = variant.cfg();
+        quote!(#cfg (#name, ::core::mem::size_of::<#stored_ty>()))
+    });
+    quote! {
+        impl #ident {
+            /// The name of each variant and the size in bytes of its payload, as held by the enum.
+            #vis const SIZE_REPORT: &'static [(&'static str, usize)] = &[#(#entries),*];
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitInt, Result, Visibility,
+};
+
+use super::{kw, snake_ident, Eq, VariantOptions, WrappedVariant};
+
+/// `box_above = bytes`
+pub struct BoxAbove {
+    pub box_above: kw::box_above,
+    pub value: Eq<LitInt>,
+}
+impl Parse for BoxAbove {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let box_above = input.parse()?;
+        let value: Eq<LitInt> = input.parse()?;
+        value.value.base10_parse::<usize>()?;
+        Ok(Self { box_above, value })
+    }
+}
+impl ToTokens for BoxAbove {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.box_above.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+/// How the payload of a variant is stored in the enum.
+#[derive(Clone)]
+pub enum Storage {
+    /// The payload itself.
+    Inline,
+    /// `Box<Payload>`, from `#[attribute(boxed)]`.
+    Boxed,
+    /// `Box<Payload>` when the payload is larger than `bytes`, the payload itself otherwise, from
+    /// `box_above = bytes`. The size is only known by the compiler, so the choice is made by the
+    /// `Storage` trait of the hidden `module` generated by [`generate_storage_module`].
+    Above { module: Ident, bytes: LitInt },
+}
+impl Storage {
+    pub fn new(enum_ident: &Ident, options: &VariantOptions, box_above: Option<&BoxAbove>) -> Self {
+        match (&options.boxed, box_above) {
+            (Some(_), _) => Storage::Boxed,
+            (None, Some(box_above)) => Storage::Above {
+                module: storage_module(enum_ident),
+                bytes: box_above.value.value.clone(),
+            },
+            (None, None) => Storage::Inline,
+        }
+    }
+}
+
+/// The name of the module generated by [`generate_storage_module`].
+pub fn storage_module(enum_ident: &Ident) -> Ident {
+    format_ident!("__{}_storage", snake_ident(enum_ident))
+}
+
+/// Generates the hidden module choosing between `T` and `Box<T>` at compile time for `box_above`.
+pub fn generate_storage_module(vis: &Visibility, enum_ident: &Ident) -> TokenStream {
+    let module = storage_module(enum_ident);
+    quote! {
+        #[doc(hidden)]
+        #vis mod #module {
+            pub struct Select<const BOXED: bool>;
+            pub trait Storage<T> {
+                type Stored;
+                fn store(value: T) -> Self::Stored;
+                fn load(stored: Self::Stored) -> T;
+                fn get(stored: &Self::Stored) -> &T;
+                fn get_mut(stored: &mut Self::Stored) -> &mut T;
+            }
+            impl<T> Storage<T> for Select<false> {
+                type Stored = T;
+                fn store(value: T) -> T {
+                    value
+                }
+                fn load(stored: T) -> T {
+                    stored
+                }
+                fn get(stored: &T) -> &T {
+                    stored
+                }
+                fn get_mut(stored: &mut T) -> &mut T {
+                    stored
+                }
+            }
+            impl<T> Storage<T> for Select<true> {
+                type Stored = ::std::boxed::Box<T>;
+                fn store(value: T) -> ::std::boxed::Box<T> {
+                    ::std::boxed::Box::new(value)
+                }
+                fn load(stored: ::std::boxed::Box<T>) -> T {
+                    *stored
+                }
+                fn get(stored: &::std::boxed::Box<T>) -> &T {
+                    stored
+                }
+                fn get_mut(stored: &mut ::std::boxed::Box<T>) -> &mut T {
+                    stored
+                }
+            }
+        }
+    }
+}
+
+impl WrappedVariant {
+    /// `<Select<..> as Storage<Payload>>` for `box_above`.
+    fn selected(&self, module: &Ident, bytes: &LitInt) -> TokenStream {
+        let ty = &self.ty;
+        quote! {
+            <#module::Select<{ ::core::mem::size_of::<#ty>() > #bytes }> as #module::Storage<#ty>>
+        }
+    }
+
+    /// The type held by the enum variant.
+    pub fn stored_ty(&self) -> TokenStream {
+        let ty = &self.ty;
+        match &self.storage {
+            Storage::Inline => ty.to_token_stream(),
+            Storage::Boxed => quote!(::std::boxed::Box<#ty>),
+            Storage::Above { module, bytes } => {
+                let selected = self.selected(module, bytes);
+                quote!(#selected::Stored)
+            }
+        }
+    }
+
+    /// Turns the payload `value` into what the enum variant holds.
+    pub fn store(&self, value: TokenStream) -> TokenStream {
+        match &self.storage {
+            Storage::Inline => value,
+            Storage::Boxed => quote!(::std::boxed::Box::new(#value)),
+            Storage::Above { module, bytes } => {
+                let selected = self.selected(module, bytes);
+                quote!(#selected::store(#value))
+            }
+        }
+    }
+
+    /// Turns what the enum variant holds back into the payload.
+    pub fn load(&self, stored: TokenStream) -> TokenStream {
+        match &self.storage {
+            Storage::Inline => stored,
+            Storage::Boxed => quote!(*#stored),
+            Storage::Above { module, bytes } => {
+                let selected = self.selected(module, bytes);
+                quote!(#selected::load(#stored))
+            }
+        }
+    }
+
+    /// Turns a reference to what the enum variant holds into a reference to the payload.
+    pub fn get(&self, stored: TokenStream) -> TokenStream {
+        match &self.storage {
+            Storage::Inline => stored,
+            Storage::Boxed => quote!(&**#stored),
+            Storage::Above { module, bytes } => {
+                let selected = self.selected(module, bytes);
+                quote!(#selected::get(#stored))
+            }
+        }
+    }
+
+    /// Turns a mutable reference to what the enum variant holds into one to the payload.
+    pub fn get_mut(&self, stored: TokenStream) -> TokenStream {
+        match &self.storage {
+            Storage::Inline => stored,
+            Storage::Boxed => quote!(&mut **#stored),
+            Storage::Above { module, bytes } => {
+                let selected = self.selected(module, bytes);
+                quote!(#selected::get_mut(#stored))
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::ToTokens;
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    Error, LitStr, Result,
+};
+
+use super::{ident_of, kw, screaming_snake_case, snake_case, Eq};
+
+/// `name = "..."`, a template for the identifiers of the generated structs.
+///
+/// `{Enum}` and `{Variant}` are replaced by the identifiers as written, `{enum}` and `{variant}` by
+/// their `snake_case` form and `{ENUM}` and `{VARIANT}` by their `SCREAMING_SNAKE_CASE` form.
+pub struct NameTemplate {
+    pub name: kw::name,
+    pub value: Eq<LitStr>,
+}
+impl NameTemplate {
+    const PLACEHOLDERS: [&'static str; 6] =
+        ["Enum", "Variant", "enum", "variant", "ENUM", "VARIANT"];
+
+    fn render_str(template: &str, enum_name: &str, variant_name: &str) -> String {
+        template
+            .replace("{Enum}", enum_name)
+            .replace("{Variant}", variant_name)
+            .replace("{enum}", &snake_case(enum_name))
+            .replace("{variant}", &snake_case(variant_name))
+            .replace("{ENUM}", &screaming_snake_case(enum_name))
+            .replace("{VARIANT}", &screaming_snake_case(variant_name))
+    }
+
+    /// The identifier of the struct generated for `variant` of `ident`.
+    pub fn render(&self, ident: &Ident, variant: &Ident) -> Ident {
+        let name = Self::render_str(
+            &self.value.value.value(),
+            &ident.unraw().to_string(),
+            &variant.unraw().to_string(),
+        );
+        ident_of(&name, variant.span())
+    }
+}
+impl Parse for NameTemplate {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let this = Self {
+            name: input.parse()?,
+            value: input.parse()?,
+        };
+        let template = this.value.value.value();
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..].find('}').map(|end| start + end);
+            let placeholder = end.map(|end| &rest[start + 1..end]);
+            match placeholder {
+                Some(placeholder) if Self::PLACEHOLDERS.contains(&placeholder) => {
+                    rest = &rest[end.unwrap() + 1..]
+                }
+                _ => Err(Error::new_spanned(
+                    &this.value.value,
+                    "valid placeholders are `{Enum}`, `{Variant}`, `{enum}`, `{variant}`, `{ENUM}` and `{VARIANT}`",
+                ))?,
+            }
+        }
+        if syn::parse_str::<Ident>(&Self::render_str(&template, "A", "B")).is_err() {
+            Err(Error::new_spanned(
+                &this.value.value,
+                "the template must produce an identifier",
+            ))?
+        }
+        Ok(this)
+    }
+}
+impl ToTokens for NameTemplate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.name.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
@@ -0,0 +1,298 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    token, Attribute, Error, Expr, Ident, LitStr, Meta, MetaList, Path, Result, Token,
+};
+
+use super::{kw, Eq, FieldVis};
+
+/// Per-variant options given through `#[attribute(...)]`.
+///
+/// Unlike other `#[attribute(...)]`s, which are forwarded to the generated struct, these are
+/// consumed by the macro and removed from the output.
+#[derive(Default, Clone)]
+pub struct VariantOptions {
+    /// `name = "..."`: overrides the string name of the variant.
+    pub name: Option<LitStr>,
+    /// `rename = Ident`: overrides the identifier of the generated struct.
+    pub rename: Option<Ident>,
+    /// `vis = pub(crate)`: overrides the visibility of the generated struct.
+    pub vis: Option<FieldVis>,
+    /// `no_inherit_derive`: the generated struct does not get the derives of the enum.
+    pub no_inherit_derive: Option<kw::no_inherit_derive>,
+    /// `inherit_derive(Debug, ...)`: the generated struct only gets these derives of the enum.
+    pub inherit_derive: Option<InheritDerive>,
+    /// `existing`: the single field of the variant is an existing type, which is used as the payload
+    /// instead of generating a struct.
+    pub existing: Option<kw::existing>,
+    /// `boxed`: the enum variant holds `Box<Payload>`.
+    pub boxed: Option<kw::boxed>,
+}
+
+/// `inherit_derive(Path0, Path1, ...)`
+#[derive(Clone)]
+pub struct InheritDerive {
+    pub inherit_derive: kw::inherit_derive,
+    pub paren_token: token::Paren,
+    pub paths: Punctuated<Path, Token![,]>,
+}
+impl Parse for InheritDerive {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            inherit_derive: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            paths: Punctuated::parse_terminated(&content)?,
+        })
+    }
+}
+impl ToTokens for InheritDerive {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.inherit_derive.to_tokens(tokens);
+        self.paren_token
+            .surround(tokens, |tokens| self.paths.to_tokens(tokens));
+    }
+}
+
+enum VariantOption {
+    Name(kw::name, Eq<LitStr>),
+    Rename(kw::rename, Eq<Ident>),
+    Vis(kw::vis, Eq<FieldVis>),
+    NoInheritDerive(kw::no_inherit_derive),
+    InheritDerive(InheritDerive),
+    Existing(kw::existing),
+    Boxed(kw::boxed),
+}
+impl VariantOption {
+    fn peek(input: ParseStream) -> bool {
+        input.peek(kw::name)
+            || input.peek(kw::rename)
+            || input.peek(kw::vis)
+            || input.peek(kw::no_inherit_derive)
+            || input.peek(kw::inherit_derive)
+            || input.peek(kw::existing)
+            || input.peek(kw::boxed)
+    }
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::name) {
+            Ok(VariantOption::Name(input.parse()?, input.parse()?))
+        } else if lookahead.peek(kw::rename) {
+            Ok(VariantOption::Rename(input.parse()?, input.parse()?))
+        } else if lookahead.peek(kw::vis) {
+            Ok(VariantOption::Vis(input.parse()?, input.parse()?))
+        } else if lookahead.peek(kw::no_inherit_derive) {
+            Ok(VariantOption::NoInheritDerive(input.parse()?))
+        } else if lookahead.peek(kw::inherit_derive) {
+            Ok(VariantOption::InheritDerive(input.parse()?))
+        } else if lookahead.peek(kw::existing) {
+            Ok(VariantOption::Existing(input.parse()?))
+        } else if lookahead.peek(kw::boxed) {
+            Ok(VariantOption::Boxed(input.parse()?))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+fn duplicate(tokens: impl ToTokens) -> Error {
+    Error::new_spanned(tokens, "duplicate variant option")
+}
+
+/// Removes every `#[attribute(...)]` that `parse_attribute` accepts from `attrs`.
+fn take_options(
+    attrs: &mut Vec<Attribute>,
+    mut parse_attribute: impl FnMut(TokenStream) -> Result<bool>,
+) -> Result<()> {
+    let mut error = None;
+    attrs.retain(|attr| {
+        let Meta::List(MetaList { path, tokens, .. }) = &attr.meta else {
+            return true;
+        };
+        if !path.is_ident("attribute") {
+            return true;
+        }
+        match parse_attribute(tokens.clone()) {
+            Ok(consumed) => !consumed,
+            Err(err) => {
+                error.get_or_insert(err);
+                false
+            }
+        }
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Parses `tokens` as a list of options, or returns `None` when it does not start with one,
+/// eg. `#[attribute(derive(Debug))]`.
+fn parse_options<T>(
+    tokens: TokenStream,
+    peek: fn(ParseStream) -> bool,
+    parse: fn(ParseStream) -> Result<T>,
+) -> Result<Option<Punctuated<T, Token![,]>>> {
+    let parser = |input: ParseStream| -> Result<Option<Punctuated<T, Token![,]>>> {
+        if peek(input) {
+            Punctuated::parse_terminated_with(input, parse).map(Some)
+        } else {
+            input.parse::<TokenStream>()?;
+            Ok(None)
+        }
+    };
+    parser.parse2(tokens)
+}
+
+impl VariantOptions {
+    /// Removes every `#[attribute(...)]` holding options from `attrs` and collects them.
+    pub fn take(attrs: &mut Vec<Attribute>) -> Result<Self> {
+        let mut options = VariantOptions::default();
+        take_options(attrs, |tokens| options.parse_attribute(tokens))?;
+        Ok(options)
+    }
+
+    /// Returns `false` when `tokens` is not a list of options.
+    fn parse_attribute(&mut self, tokens: TokenStream) -> Result<bool> {
+        let Some(list) = parse_options(tokens, VariantOption::peek, VariantOption::parse)? else {
+            return Ok(false);
+        };
+        for option in list {
+            match option {
+                VariantOption::Name(name, value) => {
+                    if self.name.replace(value.value).is_some() {
+                        Err(duplicate(name))?
+                    }
+                }
+                VariantOption::Rename(rename, value) => {
+                    if self.rename.replace(value.value).is_some() {
+                        Err(duplicate(rename))?
+                    }
+                }
+                VariantOption::Vis(vis, value) => {
+                    if self.vis.replace(value.value).is_some() {
+                        Err(duplicate(vis))?
+                    }
+                }
+                VariantOption::NoInheritDerive(no_inherit_derive) => {
+                    if self.no_inherit_derive.replace(no_inherit_derive).is_some() {
+                        Err(duplicate(no_inherit_derive))?
+                    }
+                }
+                VariantOption::InheritDerive(inherit_derive) => {
+                    let tokens = inherit_derive.inherit_derive;
+                    if self.inherit_derive.replace(inherit_derive).is_some() {
+                        Err(duplicate(tokens))?
+                    }
+                }
+                VariantOption::Existing(existing) => {
+                    if self.existing.replace(existing).is_some() {
+                        Err(duplicate(existing))?
+                    }
+                }
+                VariantOption::Boxed(boxed) => {
+                    if self.boxed.replace(boxed).is_some() {
+                        Err(duplicate(boxed))?
+                    }
+                }
+            }
+        }
+        if let (Some(no_inherit_derive), Some(_)) = (&self.no_inherit_derive, &self.inherit_derive)
+        {
+            Err(Error::new_spanned(
+                no_inherit_derive,
+                "`no_inherit_derive` cannot be combined with `inherit_derive`",
+            ))?
+        }
+        Ok(true)
+    }
+
+    /// Rejects the options that only apply to the structs generated by `extract_variant`.
+    pub fn require_generated_struct(&self) -> Result<()> {
+        let only_extract = |tokens: &dyn ToTokens, name: &str| {
+            Err(Error::new_spanned(
+                tokens.to_token_stream(),
+                format!("`{name}` is only supported by `extract_variant`"),
+            ))
+        };
+        if let Some(rename) = &self.rename {
+            return only_extract(rename, "rename");
+        }
+        if let Some(vis) = &self.vis {
+            return only_extract(vis, "vis");
+        }
+        if let Some(no_inherit_derive) = &self.no_inherit_derive {
+            return only_extract(no_inherit_derive, "no_inherit_derive");
+        }
+        if let Some(inherit_derive) = &self.inherit_derive {
+            return only_extract(inherit_derive, "inherit_derive");
+        }
+        if let Some(existing) = &self.existing {
+            return only_extract(existing, "existing");
+        }
+        Ok(())
+    }
+}
+
+/// Per-field options given through `#[attribute(...)]` on the fields of a variant.
+#[derive(Default, Clone)]
+pub struct FieldOptions {
+    /// `vis = pub(crate)`: overrides the visibility of the field in the generated struct.
+    pub vis: Option<FieldVis>,
+    /// `default = expr`: the value used by the builder when the field is not set.
+    pub default: Option<Expr>,
+}
+
+enum FieldOption {
+    Vis(kw::vis, Eq<FieldVis>),
+    Default(kw::default, Eq<Expr>),
+}
+impl FieldOption {
+    fn peek(input: ParseStream) -> bool {
+        input.peek(kw::vis) || input.peek(kw::default)
+    }
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::vis) {
+            Ok(FieldOption::Vis(input.parse()?, input.parse()?))
+        } else if lookahead.peek(kw::default) {
+            Ok(FieldOption::Default(input.parse()?, input.parse()?))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl FieldOptions {
+    /// Removes every `#[attribute(...)]` holding options from `attrs` and collects them.
+    pub fn take(attrs: &mut Vec<Attribute>) -> Result<Self> {
+        let mut options = FieldOptions::default();
+        take_options(attrs, |tokens| options.parse_attribute(tokens))?;
+        Ok(options)
+    }
+
+    /// Returns `false` when `tokens` is not a list of options.
+    fn parse_attribute(&mut self, tokens: TokenStream) -> Result<bool> {
+        let Some(list) = parse_options(tokens, FieldOption::peek, FieldOption::parse)? else {
+            return Ok(false);
+        };
+        for option in list {
+            match option {
+                FieldOption::Vis(vis, value) => {
+                    if self.vis.replace(value.value).is_some() {
+                        Err(duplicate(vis))?
+                    }
+                }
+                FieldOption::Default(default, value) => {
+                    if self.default.replace(value.value).is_some() {
+                        Err(duplicate(default))?
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+}
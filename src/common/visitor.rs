@@ -0,0 +1,143 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    token, Error, Ident, Result, Visibility,
+};
+
+use super::{kw, snake_ident, Parenthesized, WrappedVariant};
+
+/// `visitor` or `visitor(default)`
+pub struct VisitorParam {
+    pub visitor: kw::visitor,
+    pub default: Option<Parenthesized<kw::default>>,
+}
+impl VisitorParam {
+    /// Whether every `visit_*` method falls back to `visit_other`.
+    pub fn has_default(&self) -> bool {
+        self.default.is_some()
+    }
+}
+impl Parse for VisitorParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            visitor: input.parse()?,
+            default: if input.peek(token::Paren) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+        })
+    }
+}
+impl ToTokens for VisitorParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.visitor.to_tokens(tokens);
+        self.default.to_tokens(tokens);
+    }
+}
+
+/// Generates `{Enum}Visitor`, `{Enum}VisitorMut` and `{Enum}VisitorOwned` along with the
+/// `accept`, `accept_mut` and `accept_owned` methods that dispatch to them.
+pub fn generate_visitor(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[WrappedVariant],
+    param: &VisitorParam,
+) -> Result<TokenStream> {
+    let visitor = format_ident!("{ident}Visitor");
+    let visitor_mut = format_ident!("{ident}VisitorMut");
+    let visitor_owned = format_ident!("{ident}VisitorOwned");
+
+    let ids: Vec<_> = variants.iter().map(|variant| &variant.id).collect();
+    let methods: Vec<_> = ids
+        .iter()
+        .map(|id| format_ident!("visit_{}", snake_ident(id)))
+        .collect();
+    // With `default`, the fallback sits next to the per-variant methods in each trait.
+    if param.has_default() {
+        if let Some(id) = ids
+            .iter()
+            .zip(&methods)
+            .find_map(|(id, method)| (method == "visit_other").then_some(id))
+        {
+            Err(Error::new_spanned(
+                id,
+                "`visit_other` of this variant conflicts with the fallback of `visitor(default)`",
+            ))?
+        }
+    }
+    let args: Vec<_> = ids.iter().map(|id| snake_ident(id)).collect();
+    let tys: Vec<_> = variants.iter().map(|variant| &variant.ty).collect();
+    let gets = variants.iter().map(|variant| variant.get(quote!(value)));
+    let get_muts = variants
+        .iter()
+        .map(|variant| variant.get_mut(quote!(value)));
+    let loads = variants.iter().map(|variant| variant.load(quote!(value)));
+    let cfgs: Vec<_> = variants.iter().map(WrappedVariant::cfg).collect();
+
+    let flavor = |name: &Ident, doc: &str, param_ty: &dyn Fn(&syn::Type) -> TokenStream| {
+        let param_tys = tys.iter().map(|ty| param_ty(ty));
+        let (bodies, visit_other) = if param.has_default() {
+            (
+                args.iter()
+                    .map(|arg| quote!({ let _ = #arg; self.visit_other() }))
+                    .collect::<Vec<_>>(),
+                quote! {
+                    /// Called by every `visit_*` method that is not overridden.
+                    fn visit_other(&mut self) -> Self::Output;
+                },
+            )
+        } else {
+            (methods.iter().map(|_| quote!(;)).collect(), quote!())
+        };
+        quote! {
+            #[doc = #doc]
+            #vis trait #name {
+                type Output;
+                #(#cfgs fn #methods(&mut self, #args: #param_tys) -> Self::Output #bodies)*
+                #visit_other
+            }
+        }
+    };
+
+    let visitor_trait = flavor(
+        &visitor,
+        &format!("Visits a borrowed [`{ident}`], see [`{ident}::accept`]."),
+        &|ty| quote!(&#ty),
+    );
+    let visitor_mut_trait = flavor(
+        &visitor_mut,
+        &format!("Visits a mutably borrowed [`{ident}`], see [`{ident}::accept_mut`]."),
+        &|ty| quote!(&mut #ty),
+    );
+    let visitor_owned_trait = flavor(
+        &visitor_owned,
+        &format!("Visits an owned [`{ident}`], see [`{ident}::accept_owned`]."),
+        &|ty| quote!(#ty),
+    );
+
+    Ok(quote! {
+        #visitor_trait
+        #visitor_mut_trait
+        #visitor_owned_trait
+
+        impl #ident {
+            #vis fn accept<V: #visitor + ?Sized>(&self, visitor: &mut V) -> V::Output {
+                match *self {
+                    #(#cfgs #ident::#ids(ref value) => visitor.#methods(#gets),)*
+                }
+            }
+            #vis fn accept_mut<V: #visitor_mut + ?Sized>(&mut self, visitor: &mut V) -> V::Output {
+                match *self {
+                    #(#cfgs #ident::#ids(ref mut value) => visitor.#methods(#get_muts),)*
+                }
+            }
+            #vis fn accept_owned<V: #visitor_owned + ?Sized>(self, visitor: &mut V) -> V::Output {
+                match self {
+                    #(#cfgs #ident::#ids(value) => visitor.#methods(#loads),)*
+                }
+            }
+        }
+    })
+}
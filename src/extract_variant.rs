@@ -3,19 +3,30 @@ use std::fmt::{Debug, Write};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
 use syn::{
-    parse::{Parse, Parser},
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser},
+    parse_quote,
     punctuated::{Pair, Punctuated},
     token::{self, Comma},
-    AngleBracketedGenericArguments, Attribute, Error, Expr, ExprLit, FieldsNamed, GenericArgument,
-    Generics, ItemEnum, ItemStruct, Lifetime, Lit, LitStr, Meta, MetaList, MetaNameValue,
-    ParenthesizedGenericArguments, Path, PathArguments, PathSegment, Result, ReturnType, Token,
-    Type, TypePath, Variant,
+    AngleBracketedGenericArguments, Attribute, Error, Expr, ExprLit, Field, Fields, FieldsNamed,
+    GenericArgument, Generics, ItemEnum, ItemStruct, Lifetime, Lit, LitStr, Meta, MetaList,
+    MetaNameValue, ParenthesizedGenericArguments, Path, PathArguments, PathSegment, Result,
+    ReturnType, Token, Type, TypePath, Variant, Visibility,
 };
 
 use tap::prelude::*;
 
 use crate::common::{
-    generate_conversion_impl, ident, no_impl_value, path_id, Args, WrappedVariant,
+    cfg_combinations, cfg_count, derives_default, enabled_methods, generate_borrowed,
+    generate_conversion_impl, generate_default_impl, generate_fold, generate_from_str,
+    generate_iter, generate_module, generate_names, generate_parse_error, generate_require,
+    generate_size_assertions, generate_size_report, generate_storage_module, generate_tag,
+    generate_variant_constructors, generate_visitor, ident, kw, nested_vis, no_impl_value, path_id,
+    remove_default_derive, snake_ident, take_default_variant, variant_names, Args, BoxAbove, Cfg,
+    DefaultParam, FieldOptions, FieldVisParam, IterParam, MaxSize, Module, NameTemplate,
+    Parenthesized, RenameAll, Require, Storage, VariantOptions, VisitorParam, WrappedVariant,
+    BORROWED_METHODS, FOLD_METHODS, ITER_DEFAULT_METHODS, ITER_METHODS, NAMES_METHODS, TAG_METHODS,
+    VISITOR_METHODS,
 };
 
 pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
@@ -26,7 +37,35 @@ pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
         implement_conversions,
         style,
         derive_exclude,
+        forward_attrs,
+        struct_attrs,
+        visitor,
+        fold,
+        borrowed,
+        views,
+        names,
+        rename_all,
+        tag,
+        from_str,
+        reflect,
+        field_access,
+        iter,
+        module,
+        field_vis,
+        default,
+        wrap_existing,
+        box_above,
+        max_size,
+        size_report,
+        require,
+        common,
+        shared,
+        new,
+        tuple,
+        constructors,
+        builder,
     } = Config::new(params, &item_enum);
+    let field_vis = field_vis.as_ref().map(FieldVisParam::value);
 
     let ItemEnum {
         attrs,
@@ -38,84 +77,283 @@ pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
         variants,
     } = &item_enum;
 
-    let global_derive = attrs
-        .iter()
-        .filter(|attr| attr.path().is_ident("derive"))
-        .map(|attr| -> Result<Attribute> {
-            Ok(Attribute {
-                meta: if let Meta::List(list) = &attr.meta {
-                    let a = Punctuated::<Path, Token![,]>::parse_terminated
-                        .parse2(list.tokens.clone())?
-                        .into_iter()
-                        .filter(|path| !derive_exclude.contains(path));
-                    Meta::List(MetaList {
-                        tokens: quote! { #(#a),* },
-                        ..list.clone()
-                    })
-                } else {
-                    attr.meta.clone()
-                },
-                ..attr.clone()
+    // The `#[derive(...)]`s of the enum, keeping only the paths accepted by `inherit`.
+    let inherited_derive = |inherit: &dyn Fn(&Path) -> bool| {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("derive"))
+            .map(|attr| -> Result<Attribute> {
+                Ok(Attribute {
+                    meta: if let Meta::List(list) = &attr.meta {
+                        let a = Punctuated::<Path, Token![,]>::parse_terminated
+                            .parse2(list.tokens.clone())?
+                            .into_iter()
+                            .filter(|path| !derive_exclude.contains(path) && inherit(path));
+                        Meta::List(MetaList {
+                            tokens: quote! { #(#a),* },
+                            ..list.clone()
+                        })
+                    } else {
+                        attr.meta.clone()
+                    },
+                    ..attr.clone()
+                })
             })
+            .collect::<Result<Vec<_>>>()
+    };
+
+    let forwarded_attrs: Vec<Attribute> = attrs
+        .iter()
+        .filter(|attr| forward_attrs.iter().any(|path| attr.path() == path))
+        .cloned()
+        .collect();
+    let struct_attrs: Vec<Attribute> = struct_attrs
+        .into_iter()
+        .map(|meta| parse_quote!(#[#meta]))
+        .collect();
+
+    // Options are taken out of the variants here, so everything below only sees the
+    // `#[attribute(...)]`s that are meant to be forwarded.
+    let mut variants: Vec<Variant> = variants.iter().cloned().collect();
+    let variant_options = variants
+        .iter_mut()
+        .map(|variant| VariantOptions::take(&mut variant.attrs))
+        .collect::<Result<Vec<_>>>()?;
+    let mut field_options = variants
+        .iter_mut()
+        .map(|variant| {
+            variant
+                .fields
+                .iter_mut()
+                .map(|field| FieldOptions::take(&mut field.attrs))
+                .collect::<Result<Vec<_>>>()
         })
         .collect::<Result<Vec<_>>>()?;
+    let default_variant = take_default_variant(
+        default.as_ref(),
+        variants
+            .iter_mut()
+            .map(|variant| (&variant.ident, &mut variant.attrs)),
+    )?;
 
-    let wrap_variant = |variant: &Variant| {
-        let attrs = variant.attrs.clone();
-        let id = variant.ident.clone();
-        let ty = Type::Path(TypePath {
-            qself: None,
-            path: Path::from(map_ident(&id)),
-        });
-        WrappedVariant { attrs, id, ty }
+    // `Default` is implemented below rather than derived, since in wrap style the variant
+    // holding `#[default]` is no longer a unit variant.
+    let mut enum_attrs = attrs.clone();
+    if default_variant.is_some() {
+        remove_default_derive(&mut enum_attrs)?;
+    }
+
+    let struct_idents: Vec<Ident> = variants
+        .iter()
+        .zip(&variant_options)
+        .map(|(variant, options)| {
+            options
+                .rename
+                .clone()
+                .unwrap_or_else(|| map_ident(&variant.ident))
+        })
+        .collect();
+
+    // Variants whose single field is an existing type, which is used as the payload like in
+    // `variant_wrapper` instead of generating a struct.
+    if let Some(wrap_existing) = &wrap_existing {
+        style.require_wrap(wrap_existing, "wrap_existing")?;
+    }
+    let existing = variants
+        .iter()
+        .zip(&variant_options)
+        .map(|(variant, options)| -> Result<bool> {
+            let single =
+                matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1);
+            if let Some(existing) = &options.existing {
+                style.require_wrap(existing, "existing")?;
+                if !single {
+                    Err(Error::new_spanned(
+                        existing,
+                        "`existing` requires a tuple-like variant with exactly 1 field",
+                    ))?
+                }
+            }
+            Ok(options.existing.is_some() || wrap_existing.is_some() && single)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The `shared` fields go first in every generated struct, so tuple-like and unit variants
+    // become named ones, with `_0`, `_1`, ... for their fields.
+    for ((variant, field_options), existing) in
+        variants.iter_mut().zip(&mut field_options).zip(&existing)
+    {
+        if !shared.is_empty() && !existing {
+            inject_shared(&mut variant.fields, &shared)?;
+            field_options.splice(0..0, shared.iter().map(|_| FieldOptions::default()));
+        }
+    }
+
+    // Field defaults are only used by the builders, which only exist for generated structs with
+    // named fields. The setters of a builder are named after the fields, so these cannot take the
+    // names of its other methods.
+    for ((variant, field_options), existing) in variants.iter().zip(&field_options).zip(&existing) {
+        let has_builder =
+            builder.is_some() && !existing && matches!(variant.fields, Fields::Named(_));
+        if let Some(default) = field_options
+            .iter()
+            .find_map(|options| options.default.as_ref())
+        {
+            if !has_builder {
+                Err(Error::new_spanned(
+                    default,
+                    "`default` requires `builder` and a generated struct with named fields",
+                ))?
+            }
+        }
+        if has_builder {
+            for name in variant
+                .fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+            {
+                if ["build", "build_enum", "builder"].contains(&name.unraw().to_string().as_str()) {
+                    Err(Error::new_spanned(
+                        name,
+                        format!("`{name}` conflicts with a method of the generated builder"),
+                    ))?
+                }
+            }
+        }
+    }
+
+    // Boxing only changes how the enum holds the payload, in "keep" style there is none.
+    if let Some(box_above) = &box_above {
+        style.require_wrap(box_above, "box_above")?;
+    }
+    for options in &variant_options {
+        if let Some(boxed) = &options.boxed {
+            style.require_wrap(boxed, "boxed")?;
+        }
+    }
+
+    // Inside `module`, the structs need to be visible one level up to be as visible as the enum.
+    let struct_vis = match &module {
+        Some(_) => nested_vis(vis),
+        None => vis.clone(),
     };
 
-    let wrapped_variants: Vec<WrappedVariant> = variants.iter().map(wrap_variant).collect();
+    let wrap_variant =
+        |variant: &Variant, options: &VariantOptions, struct_ident: &Ident, existing: bool| {
+            let attrs = variant.attrs.clone();
+            let id = variant.ident.clone();
+            let ty = if existing {
+                variant.fields.iter().next().unwrap().ty.clone()
+            } else {
+                Type::Path(TypePath {
+                    qself: None,
+                    path: match &module {
+                        Some(module) => {
+                            let module = module.ident();
+                            parse_quote!(#module::#struct_ident)
+                        }
+                        None => Path::from(struct_ident.clone()),
+                    },
+                })
+            };
+            WrappedVariant {
+                attrs,
+                id,
+                ty,
+                options: options.clone(),
+                storage: Storage::new(ident, options, box_above.as_ref()),
+            }
+        };
 
-    let variants_def = variants.iter().map(|variant| match style {
-        Style::Wrap => wrap_variant(variant).into_token_stream(),
-        Style::Keep => variant.to_token_stream(),
-    });
+    let wrapped_variants: Vec<WrappedVariant> = variants
+        .iter()
+        .zip(&variant_options)
+        .zip(&struct_idents)
+        .zip(&existing)
+        .map(|(((variant, options), struct_ident), existing)| {
+            wrap_variant(variant, options, struct_ident, *existing)
+        })
+        .collect();
+
+    let variants_def = variants
+        .iter()
+        .zip(&wrapped_variants)
+        .map(|(variant, wrapped)| -> Result<TokenStream> {
+            Ok(match style {
+                Style::Wrap => wrapped.to_token_stream(),
+                Style::Keep => {
+                    let mut variant = variant.clone();
+                    route_field_attrs(&mut variant.fields, "enum_attribute", "attribute")?;
+                    variant.to_token_stream()
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-    let generate_struct = |Variant {
-                               attrs,
-                               ident,
-                               fields,
-                               discriminant: _,
-                           }: &Variant|
+    let generate_struct = |(
+        Variant {
+            attrs,
+            ident: _,
+            fields,
+            discriminant: _,
+        },
+        struct_ident,
+        options,
+        field_options,
+        is_default,
+    ): (&Variant, &Ident, &VariantOptions, &Vec<FieldOptions>, bool)|
      -> Result<ItemStruct> {
+        let vis = match &options.vis {
+            Some(vis) => vis.resolve(&struct_vis, module.is_some()),
+            None => struct_vis.clone(),
+        };
+        let mut fields = fields.clone();
+        route_field_attrs(&mut fields, "attribute", "enum_attribute")?;
+        for (field, options) in fields.iter_mut().zip(field_options) {
+            field.vis = match options.vis.as_ref().or(field_vis) {
+                Some(field_vis) => field_vis.resolve(&vis, module.is_some()),
+                None => vis.clone(),
+            };
+        }
+        let derive = match (&options.no_inherit_derive, &options.inherit_derive) {
+            (Some(_), _) => Vec::new(),
+            (None, Some(inherit_derive)) => inherited_derive(&|path| {
+                inherit_derive.paths.iter().any(|inherit| inherit == path)
+            })?,
+            (None, None) => inherited_derive(&|_| true)?,
+        };
+        let mut attrs = attrs
+            .iter()
+            .filter_map(|attr| {
+                let path = attr.path();
+                if path.is_ident("attribute") {
+                    Some(unwrap_attribute(attr))
+                } else if path.is_ident("doc") || path.is_ident("cfg") || path.is_ident("cfg_attr")
+                {
+                    Some(Ok(attr.clone()))
+                } else {
+                    None
+                }
+            })
+            .collect::<Result<Vec<_>>>()?
+            .tap_mut(|attrs| {
+                attrs.extend(derive);
+                attrs.extend(forwarded_attrs.iter().cloned());
+                attrs.extend(struct_attrs.iter().cloned());
+            });
+        // The generated `Default` of the enum defers to the struct of the default variant.
+        if is_default && matches!(style, Style::Wrap) && !derives_default(&attrs)? {
+            attrs.push(parse_quote!(#[derive(::core::default::Default)]));
+        }
         Ok(ItemStruct {
-            attrs: attrs
-                .iter()
-                .filter_map(|attr| {
-                    let path = attr.path();
-                    if path.is_ident("attribute") {
-                        Some(if let Meta::List(MetaList { tokens, .. }) = &attr.meta {
-                            syn::parse2::<Meta>(tokens.clone()).map(|meta| Attribute {
-                                meta,
-                                ..attr.clone()
-                            })
-                        } else {
-                            Err(Error::new_spanned(
-                                attr.meta.clone(),
-                                "must be in the form of `#[attribute(...)]`",
-                            ))
-                        })
-                    } else if path.is_ident("doc") {
-                        Some(Ok(attr.clone()))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Result<Vec<_>>>()?
-                .tap_mut(|attrs| attrs.extend_from_slice(&global_derive)),
-            vis: vis.clone(),
+            attrs,
+            vis,
             struct_token: token::Struct {
                 span: Span::call_site(),
             },
-            ident: map_ident(&ident),
+            ident: struct_ident.clone(),
             generics: Generics::default(), // TODO
-            fields: fields.clone(),
+            fields,
             semi_token: None,
         })
     };
@@ -123,7 +361,15 @@ pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
     // let generated_structs = variants.iter().map(generate_struct);
     let generated_structs = variants
         .iter()
-        .map(generate_struct)
+        .zip(&struct_idents)
+        .zip(&variant_options)
+        .zip(&field_options)
+        .enumerate()
+        .filter(|(i, _)| !existing[*i])
+        .map(|(i, (((variant, struct_ident), options), field_options))| {
+            let is_default = default_variant == Some(i);
+            generate_struct((variant, struct_ident, options, field_options, is_default))
+        })
         .collect::<Result<Vec<_>>>()?;
     // .try_fold(quote! {}, |acc, s| {
     //     s.map(
@@ -149,18 +395,202 @@ pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
     //     )
     // })?;
 
-    let conversion_impls = wrapped_variants
-        .iter()
-        .map(|WrappedVariant { attrs: _, id, ty }| {
-            if implement_conversions {
-                match style {
-                    Style::Wrap => generate_conversion_impl(ident, id, ty),
-                    Style::Keep => todo!(),
-                }
-            } else {
-                quote!()
+    let conversion_impls = wrapped_variants.iter().map(|variant| {
+        if implement_conversions {
+            match style {
+                Style::Wrap => generate_conversion_impl(ident, variant),
+                Style::Keep => todo!(),
             }
+        } else {
+            quote!()
+        }
+    });
+
+    let visitor_impl = visitor
+        .map(|visitor| -> Result<TokenStream> {
+            style.require_wrap(&visitor, "visitor")?;
+            generate_visitor(vis, ident, &wrapped_variants, &visitor)
+        })
+        .transpose()?;
+    let fold_impl = fold
+        .map(|fold| -> Result<TokenStream> {
+            style.require_wrap(fold, "fold")?;
+            generate_fold(vis, ident, &wrapped_variants)
+        })
+        .transpose()?;
+    let borrowed_impl = borrowed
+        .map(|borrowed| -> Result<TokenStream> {
+            style.require_wrap(borrowed, "borrowed")?;
+            Ok(generate_borrowed(vis, ident, &wrapped_variants))
+        })
+        .transpose()?;
+
+    let views_impl = views
+        .map(|views| -> Result<TokenStream> {
+            style.require_keep(views, "views")?;
+            Ok(generate_views(vis, ident, &variants, &struct_idents))
+        })
+        .transpose()?;
+
+    let variant_names = variant_names(&wrapped_variants, rename_all.as_ref());
+    // Existing types may not be local to the crate, or may be shared with another enum, so only the
+    // generated structs get `NAME`.
+    let names_impl =
+        names.then(|| generate_names(vis, ident, &wrapped_variants, &existing, &variant_names));
+    let parse_error = (tag || from_str.is_some()).then(|| generate_parse_error(vis, ident));
+    let tag_impl = tag.then(|| generate_tag(vis, ident, &wrapped_variants, &variant_names));
+    let from_str_impl = from_str
+        .map(|from_str| -> Result<TokenStream> {
+            style.require_wrap(from_str, "from_str")?;
+            Ok(generate_from_str(ident, &wrapped_variants, &variant_names))
+        })
+        .transpose()?;
+    let reflect_impl = reflect.then(|| {
+        generate_reflect(
+            vis,
+            ident,
+            &variants,
+            &wrapped_variants,
+            &existing,
+            &variant_names,
+        )
+    });
+    let (field_access_struct_impls, field_access_impl) = if field_access {
+        generate_field_access(vis, &struct_vis, ident, &variants, &struct_idents, &style)
+    } else {
+        Default::default()
+    };
+    let constructor_impls = generate_constructors(
+        &struct_vis,
+        &variants,
+        &struct_idents,
+        &existing,
+        new.as_ref(),
+        tuple,
+    );
+    let (builder_struct_impls, builder_impl) = if let Some(builder) = &builder {
+        // The generated structs are those of the variants that are not `existing`.
+        let structs: Vec<_> = wrapped_variants
+            .iter()
+            .zip(&field_options)
+            .zip(&existing)
+            .filter(|(_, existing)| !**existing)
+            .zip(&generated_structs)
+            .map(|(((wrapped, field_options), _), item_struct)| {
+                (wrapped, field_options.as_slice(), item_struct)
+            })
+            .collect();
+        generate_builders(vis, ident, builder, &structs, &style)
+    } else {
+        Default::default()
+    };
+    let iter_default = iter.as_ref().is_some_and(IterParam::has_default);
+    let iter_impl = iter.map(|iter| {
+        let constructors = iter.has_default().then(|| {
+            variants
+                .iter()
+                .zip(&wrapped_variants)
+                .map(|(variant, wrapped)| default_constructor(ident, variant, wrapped, &style))
+                .collect::<Vec<_>>()
         });
+        generate_iter(vis, ident, &wrapped_variants, constructors.as_deref())
+    });
+
+    let default_impl = default_variant.map(|i| {
+        let (variant, wrapped) = (&variants[i], &wrapped_variants[i]);
+        let value = match style {
+            Style::Wrap => {
+                let (id, ty) = (&wrapped.id, &wrapped.ty);
+                let value = wrapped.store(quote!(<#ty as ::core::default::Default>::default()));
+                quote!(#ident::#id(#value))
+            }
+            Style::Keep => default_constructor(ident, variant, wrapped, &style),
+        };
+        generate_default_impl(ident, value)
+    });
+
+    let size_assertions = max_size
+        .as_ref()
+        .map(|max_size| -> Result<TokenStream> {
+            style.require_wrap(max_size, "max_size")?;
+            Ok(generate_size_assertions(ident, &wrapped_variants, max_size))
+        })
+        .transpose()?;
+    let size_report_impl = (max_size.is_some() || size_report.is_some())
+        .then(|| -> Result<TokenStream> {
+            if let Some(size_report) = &size_report {
+                style.require_wrap(size_report, "size_report")?;
+            }
+            Ok(generate_size_report(vis, ident, &wrapped_variants))
+        })
+        .transpose()?;
+
+    let shared_names: Vec<Ident> = shared
+        .iter()
+        .filter_map(|field| field.ident.clone())
+        .collect();
+    // The methods generated on the enum, which the `common` accessors and then `constructors`
+    // cannot take the names of.
+    let mut taken = enabled_methods(&[
+        (visitor_impl.is_some(), VISITOR_METHODS),
+        (fold_impl.is_some(), FOLD_METHODS),
+        (borrowed_impl.is_some(), BORROWED_METHODS),
+        (names, NAMES_METHODS),
+        (tag, TAG_METHODS),
+        (iter_impl.is_some(), ITER_METHODS),
+        (iter_default, ITER_DEFAULT_METHODS),
+        (field_access, FIELD_ACCESS_METHODS),
+    ]);
+    if views_impl.is_some() {
+        for variant in variants.iter().filter(|variant| !variant.fields.is_empty()) {
+            let snake = snake_ident(&variant.ident);
+            taken.extend([format!("as_{snake}_view"), format!("as_{snake}_view_mut")]);
+        }
+    }
+    let (common_impl, common_methods) = (common.is_some() || !shared.is_empty())
+        .then(|| {
+            generate_common(
+                vis,
+                ident,
+                &variants,
+                &style,
+                common.as_ref(),
+                &shared_names,
+                &taken,
+            )
+        })
+        .transpose()?
+        .unzip();
+    taken.extend(common_methods.into_iter().flatten());
+    let variant_constructors_impl = constructors
+        .then(|| {
+            generate_variant_constructors(
+                vis,
+                ident,
+                &wrapped_variants,
+                variant_constructors(ident, &variants, &wrapped_variants, &existing, &style),
+                &taken,
+            )
+        })
+        .transpose()?;
+    let require_impl = require
+        .as_ref()
+        .map(|require| generate_require(&wrapped_variants, require));
+
+    let storage_module = box_above
+        .is_some()
+        .then(|| generate_storage_module(vis, ident));
+
+    let structs_def = quote! {
+        #(#generated_structs)*
+        #field_access_struct_impls
+        #constructor_impls
+        #builder_struct_impls
+    };
+    let structs_def = match &module {
+        Some(module) => generate_module(vis, module.ident(), structs_def),
+        None => structs_def,
+    };
 
     // if let Some(lt_token) = item_enum.generics.lt_token {
     //     return Err(Error::new_spanned(
@@ -179,15 +609,1036 @@ pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
     // }
 
     Ok(quote! {
-        #(#attrs)*
+        #(#enum_attrs)*
         #vis #enum_token #ident {
             #(#variants_def),*
         }
-        #(#generated_structs)*
+        #storage_module
+        #structs_def
         #(#conversion_impls)*
+        #visitor_impl
+        #fold_impl
+        #borrowed_impl
+        #views_impl
+        #names_impl
+        #parse_error
+        #tag_impl
+        #from_str_impl
+        #reflect_impl
+        #field_access_impl
+        #iter_impl
+        #default_impl
+        #size_assertions
+        #size_report_impl
+        #require_impl
+        #common_impl
+        #variant_constructors_impl
+        #builder_impl
     })
 }
 
+/// Turns `#[attribute(...)]` or `#[enum_attribute(...)]` into the attribute it holds.
+fn unwrap_attribute(attr: &Attribute) -> Result<Attribute> {
+    if let Meta::List(MetaList { tokens, .. }) = &attr.meta {
+        syn::parse2::<Meta>(tokens.clone()).map(|meta| Attribute {
+            meta,
+            ..attr.clone()
+        })
+    } else {
+        let name = attr.path().to_token_stream();
+        Err(Error::new_spanned(
+            attr.meta.clone(),
+            format!("must be in the form of `#[{name}(...)]`"),
+        ))
+    }
+}
+
+/// Unwraps the `#[{keep}(...)]`s and removes the `#[{remove}(...)]`s of every field, the other
+/// attributes of the fields end up on both the enum and the struct.
+fn route_field_attrs(fields: &mut Fields, keep: &str, remove: &str) -> Result<()> {
+    for field in fields.iter_mut() {
+        field.attrs = field
+            .attrs
+            .iter()
+            .filter(|attr| !attr.path().is_ident(remove))
+            .map(|attr| {
+                if attr.path().is_ident(keep) {
+                    unwrap_attribute(attr)
+                } else {
+                    Ok(attr.clone())
+                }
+            })
+            .collect::<Result<_>>()?;
+    }
+    Ok(())
+}
+
+/// Generates `new` and, with `tuple`, the conversions from and to a tuple of the fields on each
+/// generated struct. Structs with `#[cfg(...)]` on some fields do not get the tuple conversions,
+/// as a tuple type cannot leave out an element.
+fn generate_constructors(
+    struct_vis: &Visibility,
+    variants: &[Variant],
+    struct_idents: &[Ident],
+    existing: &[bool],
+    new: Option<&NewParam>,
+    tuple: bool,
+) -> TokenStream {
+    let impls = variants
+        .iter()
+        .zip(struct_idents)
+        .zip(existing)
+        .filter(|(_, existing)| !**existing)
+        .map(|((variant, struct_ident), _)| {
+            let cfg = Cfg::of(&variant.attrs);
+            let fields = &variant.fields;
+            let names = field_names(fields);
+            let members: Vec<_> = fields.members().collect();
+            let tys: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+            let field_cfgs: Vec<_> = fields.iter().map(|field| Cfg::of(&field.attrs)).collect();
+
+            let new_impl = new.map(|new| {
+                let (params, values): (Vec<_>, Vec<_>) = names
+                    .iter()
+                    .zip(&tys)
+                    .map(|(name, ty)| match new.into {
+                        Some(_) => (
+                            quote!(#name: impl ::core::convert::Into<#ty>),
+                            quote!(::core::convert::Into::into(#name)),
+                        ),
+                        None => (quote!(#name: #ty), quote!(#name)),
+                    })
+                    .unzip();
+                let value = construct(struct_ident.to_token_stream(), fields, &values);
+                quote! {
+                    #cfg
+                    impl #struct_ident {
+                        #[allow(clippy::too_many_arguments)]
+                        #struct_vis fn new(#(#field_cfgs #params),*) -> Self {
+                            #value
+                        }
+                    }
+                }
+            });
+
+            let tuple_impl = (tuple && !fields.is_empty() && field_cfgs.iter().all(Cfg::is_empty))
+                .then(|| {
+                    let into = match fields {
+                        Fields::Named(_) => format_ident!("into_parts"),
+                        _ => format_ident!("into_tuple"),
+                    };
+                    quote! {
+                        #cfg
+                        impl ::core::convert::From<(#(#tys,)*)> for #struct_ident {
+                            fn from((#(#names,)*): (#(#tys,)*)) -> Self {
+                                #struct_ident { #(#members: #names),* }
+                            }
+                        }
+                        #cfg
+                        impl #struct_ident {
+                            #struct_vis fn #into(self) -> (#(#tys,)*) {
+                                (#(self.#members,)*)
+                            }
+                        }
+                    }
+                });
+
+            quote! {
+                #new_impl
+                #tuple_impl
+            }
+        });
+    quote!(#(#impls)*)
+}
+
+/// Generates a builder for each generated struct with named fields, and the error type returned
+/// by their `build`. Returns the builders, which go next to the structs, and the error type, which
+/// goes next to the enum.
+fn generate_builders(
+    vis: &Visibility,
+    ident: &Ident,
+    builder_param: &BuilderParam,
+    structs: &[(&WrappedVariant, &[FieldOptions], &ItemStruct)],
+    style: &Style,
+) -> (TokenStream, TokenStream) {
+    let error = format_ident!("{ident}BuildError");
+    let error_doc = format!("The error returned by the `build` of the builders of [`{ident}`].");
+    let missing_message = format!("missing fields {{:?}} to build a variant of `{ident}`");
+
+    let builders = structs
+        .iter()
+        .filter_map(|&(wrapped, field_options, item_struct)| {
+            let Fields::Named(fields) = &item_struct.fields else {
+                return None;
+            };
+            let ItemStruct {
+                attrs,
+                vis: struct_vis,
+                ident: struct_ident,
+                ..
+            } = item_struct;
+            let WrappedVariant { id, .. } = wrapped;
+            let builder = format_ident!("{struct_ident}Builder");
+            let cfg = Cfg::of(attrs);
+            let names: Vec<_> = fields
+                .named
+                .iter()
+                .map(|field| field.ident.as_ref().unwrap())
+                .collect();
+            let tys: Vec<_> = fields.named.iter().map(|field| &field.ty).collect();
+            let cfgs: Vec<_> = fields
+                .named
+                .iter()
+                .map(|field| Cfg::of(&field.attrs))
+                .collect();
+            let values = names.iter().zip(field_options).map(|(name, options)| {
+                match &options.default {
+                    Some(default) => quote!(self.#name.unwrap_or_else(|| #default)),
+                    None => quote!(::core::option::Option::unwrap(self.#name)),
+                }
+            });
+            // Every field without a default is checked before any is unwrapped, so that all the
+            // missing ones are reported.
+            let (required_cfgs, required): (Vec<&Cfg>, Vec<&Ident>) = names
+                .iter()
+                .zip(&cfgs)
+                .zip(field_options)
+                .filter(|(_, options)| options.default.is_none())
+                .map(|((name, cfg), _)| (cfg, *name))
+                .unzip();
+            let required_names = required.iter().map(|name| name.unraw().to_string());
+            let check = (!required.is_empty()).then(|| {
+                quote! {
+                    let mut missing = ::std::vec::Vec::new();
+                    #(
+                        #required_cfgs
+                        if self.#required.is_none() {
+                            missing.push(#required_names);
+                        }
+                    )*
+                    if !missing.is_empty() {
+                        return ::core::result::Result::Err(#error::MissingFields(missing));
+                    }
+                }
+            });
+            let value = match style {
+                Style::Wrap => {
+                    let value = wrapped.store(quote!(value));
+                    quote!(#ident::#id(#value))
+                }
+                Style::Keep => quote!(#ident::#id { #(#cfgs #names: value.#names),* }),
+            };
+            let setters = names.iter().zip(&tys).map(|(name, ty)| match builder_param.into {
+                Some(_) => quote! {
+                    #struct_vis fn #name(mut self, value: impl ::core::convert::Into<#ty>) -> Self {
+                        self.#name = ::core::option::Option::Some(::core::convert::Into::into(value));
+                        self
+                    }
+                },
+                None => quote! {
+                    #struct_vis fn #name(mut self, value: #ty) -> Self {
+                        self.#name = ::core::option::Option::Some(value);
+                        self
+                    }
+                },
+            });
+            let builder_doc = format!("Builds a [`{struct_ident}`] field by field.");
+            let build_enum_doc = format!(
+                "Builds the [`{struct_ident}`] as a [`{ident}::{}`].",
+                id.unraw()
+            );
+            Some(quote! {
+                #cfg
+                #[doc = #builder_doc]
+                #[derive(::core::default::Default)]
+                #struct_vis struct #builder {
+                    #(#cfgs #names: ::core::option::Option<#tys>,)*
+                }
+                #cfg
+                impl #builder {
+                    #(#cfgs #setters)*
+                    /// Builds the struct, fails with every field that is neither set nor has a
+                    /// default.
+                    #struct_vis fn build(self) -> ::core::result::Result<#struct_ident, #error> {
+                        #check
+                        ::core::result::Result::Ok(#struct_ident {
+                            #(#cfgs #names: #values,)*
+                        })
+                    }
+                    #[doc = #build_enum_doc]
+                    #struct_vis fn build_enum(self) -> ::core::result::Result<#ident, #error> {
+                        let value = self.build()?;
+                        ::core::result::Result::Ok(#value)
+                    }
+                }
+                #cfg
+                impl #struct_ident {
+                    /// A builder with no field set.
+                    #struct_vis fn builder() -> #builder {
+                        ::core::default::Default::default()
+                    }
+                }
+            })
+        });
+    let struct_impls = quote!(#(#builders)*);
+
+    let enum_impls = quote! {
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis enum #error {
+            /// Some fields without a default were not set, holds their names.
+            MissingFields(::std::vec::Vec<&'static str>),
+        }
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    #error::MissingFields(names) => ::core::write!(f, #missing_message, names),
+                }
+            }
+        }
+        impl ::std::error::Error for #error {}
+    };
+
+    (struct_impls, enum_impls)
+}
+
+/// Builds `path` from one value per field. Tuple-like fields are given positionally, as the
+/// index of a field after one with `#[cfg(...)]` depends on whether that one is enabled.
+fn construct(path: TokenStream, fields: &Fields, values: &[TokenStream]) -> TokenStream {
+    let cfgs = fields.iter().map(|field| Cfg::of(&field.attrs));
+    match fields {
+        Fields::Named(_) => {
+            let members = fields.members();
+            quote!(#path { #(#cfgs #members: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#cfgs #values),*)),
+        Fields::Unit => path,
+    }
+}
+
+/// The names of `fields`, with `_0`, `_1`, ... for those of tuple-like variants.
+fn field_names(fields: &Fields) -> Vec<Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| field.ident.clone().unwrap_or_else(|| format_ident!("_{i}")))
+        .collect()
+}
+
+/// The parameters and body of the constructor of each variant on the enum, which takes the fields
+/// of the variant, or the payload for `existing` ones.
+fn variant_constructors(
+    ident: &Ident,
+    variants: &[Variant],
+    wrapped_variants: &[WrappedVariant],
+    existing: &[bool],
+    style: &Style,
+) -> Vec<(TokenStream, TokenStream)> {
+    variants
+        .iter()
+        .zip(wrapped_variants)
+        .zip(existing)
+        .map(|((variant, wrapped), existing)| {
+            let WrappedVariant { id, ty, .. } = wrapped;
+            if *existing {
+                let value = wrapped.store(quote!(value));
+                return (quote!(value: #ty), quote!(#ident::#id(#value)));
+            }
+            let fields = &variant.fields;
+            let names: Vec<_> = field_names(fields)
+                .into_iter()
+                .map(|name| name.into_token_stream())
+                .collect();
+            let tys = fields.iter().map(|field| &field.ty);
+            let cfgs = fields.iter().map(|field| Cfg::of(&field.attrs));
+            let params = quote!(#(#cfgs #names: #tys),*);
+            let value = match style {
+                Style::Wrap => {
+                    let value = wrapped.store(construct(ty.to_token_stream(), fields, &names));
+                    quote!(#ident::#id(#value))
+                }
+                Style::Keep => construct(quote!(#ident::#id), fields, &names),
+            };
+            (params, value)
+        })
+        .collect()
+}
+
+/// Adds the `shared` fields in front of `fields`, turning them into named fields.
+fn inject_shared(fields: &mut Fields, shared: &[Field]) -> Result<()> {
+    let mut named: Punctuated<Field, Token![,]> = shared.iter().cloned().collect();
+    for (i, field) in fields.iter().enumerate() {
+        let mut field = field.clone();
+        let name = field.ident.get_or_insert_with(|| format_ident!("_{i}"));
+        if shared
+            .iter()
+            .any(|shared| shared.ident.as_ref() == Some(name))
+        {
+            Err(Error::new_spanned(
+                &name,
+                format!("`{name}` is already a shared field"),
+            ))?
+        }
+        field.colon_token.get_or_insert_with(Default::default);
+        named.push(field);
+    }
+    *fields = Fields::Named(FieldsNamed {
+        brace_token: Default::default(),
+        named,
+    });
+    Ok(())
+}
+
+/// An expression building `variant` with default values. Variants without fields are built
+/// directly, so they do not need [`Default`].
+fn default_constructor(
+    ident: &Ident,
+    variant: &Variant,
+    wrapped: &WrappedVariant,
+    style: &Style,
+) -> TokenStream {
+    let id = &variant.ident;
+    let default = quote!(::core::default::Default::default());
+    match style {
+        Style::Wrap => {
+            let ty = &wrapped.ty;
+            let value = match &variant.fields {
+                Fields::Unit => quote!(#ty),
+                Fields::Named(fields) if fields.named.is_empty() => quote!(#ty {}),
+                Fields::Unnamed(fields) if fields.unnamed.is_empty() => quote!(#ty()),
+                _ => quote!(<#ty as ::core::default::Default>::default()),
+            };
+            let value = wrapped.store(value);
+            quote!(#ident::#id(#value))
+        }
+        Style::Keep => match &variant.fields {
+            Fields::Unit => quote!(#ident::#id),
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                let cfgs = fields.named.iter().map(|field| Cfg::of(&field.attrs));
+                quote!(#ident::#id { #(#cfgs #names: #default),* })
+            }
+            Fields::Unnamed(fields) => {
+                let defaults = fields.unnamed.iter().map(|_| &default);
+                let cfgs = fields.unnamed.iter().map(|field| Cfg::of(&field.attrs));
+                quote!(#ident::#id(#(#cfgs #defaults),*))
+            }
+        },
+    }
+}
+
+/// Renders a type the way it would usually be written, eg. `Vec<u8>` rather than `Vec < u8 >`.
+fn type_string(ty: &Type) -> String {
+    let mut string = ty.to_token_stream().to_string();
+    for (from, to) in [
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ::", "::"),
+        (":: ", "::"),
+        (" ,", ","),
+        ("& ", "&"),
+        (" ;", ";"),
+        ("[ ", "["),
+        (" ]", "]"),
+        ("( ", "("),
+        (" )", ")"),
+    ] {
+        string = string.replace(from, to);
+    }
+    string
+}
+
+/// The values of the `#[doc = "..."]` attributes, one per line of doc comment.
+fn doc_strings(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(MetaNameValue {
+                path,
+                value:
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(str), ..
+                    }),
+                ..
+            }) if path.is_ident("doc") => {
+                let doc = str.value();
+                Some(doc.strip_prefix(' ').map(str::to_owned).unwrap_or(doc))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generates `{Enum}VariantInfo` and `{Enum}FieldInfo`, the `VARIANTS` constant on the enum and
+/// the `VARIANT` constant on each generated struct.
+fn generate_reflect(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[Variant],
+    wrapped_variants: &[WrappedVariant],
+    existing: &[bool],
+    names: &[String],
+) -> TokenStream {
+    let variant_info = format_ident!("{ident}VariantInfo");
+    let field_info = format_ident!("{ident}FieldInfo");
+    let variant_doc = format!("Describes a variant of [`{ident}`], see [`{ident}::VARIANTS`].");
+    let field_doc = format!("Describes a field of a [`{ident}`] variant.");
+
+    let cfgs: Vec<_> = wrapped_variants.iter().map(WrappedVariant::cfg).collect();
+    let indices: Vec<_> = (0..variants.len()).map(|i| cfg_count(&cfgs[..i])).collect();
+    let infos = variants
+        .iter()
+        .zip(wrapped_variants)
+        .zip(names)
+        .zip(&indices)
+        .map(|(((variant, wrapped), name), index)| {
+            let struct_name = type_string(&wrapped.ty);
+            let fields = variant.fields.iter().enumerate().map(|(i, field)| {
+                let cfg = Cfg::of(&field.attrs);
+                let name = field
+                    .ident
+                    .as_ref()
+                    .map_or_else(|| i.to_string(), |id| id.unraw().to_string());
+                let ty = type_string(&field.ty);
+                let docs = doc_strings(&field.attrs);
+                quote! {
+                    #cfg
+                    #field_info {
+                        name: #name,
+                        ty: #ty,
+                        docs: &[#(#docs),*],
+                    }
+                }
+            });
+            let docs = doc_strings(&variant.attrs);
+            let attributes = variant.attrs.iter().filter_map(|attr| match &attr.meta {
+                Meta::List(MetaList { path, tokens, .. }) if path.is_ident("attribute") => {
+                    Some(tokens.to_string())
+                }
+                _ => None,
+            });
+            let cfg = wrapped.cfg();
+            quote! {
+                #cfg
+                #variant_info {
+                    name: #name,
+                    index: #index,
+                    struct_name: #struct_name,
+                    fields: &[#(#fields),*],
+                    docs: &[#(#docs),*],
+                    attributes: &[#(#attributes),*],
+                }
+            }
+        });
+    // Existing types may not be local to the crate, so only the generated structs get `VARIANT`.
+    let consts = wrapped_variants
+        .iter()
+        .zip(&cfgs)
+        .zip(&indices)
+        .zip(existing)
+        .filter(|(_, existing)| !**existing)
+        .map(|(((WrappedVariant { ty, .. }, cfg), index), _)| {
+            quote! {
+                #cfg
+                impl #ty {
+                    #vis const VARIANT: &'static #variant_info = &#ident::VARIANTS[#index];
+                }
+            }
+        });
+
+    quote! {
+        #[doc = #variant_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #vis struct #variant_info {
+            /// The name of the variant, as returned by `variant_name`.
+            pub name: &'static str,
+            /// The position of the variant in the enum.
+            pub index: usize,
+            /// The name of the generated struct.
+            pub struct_name: &'static str,
+            /// The fields of the variant, in declaration order.
+            pub fields: &'static [#field_info],
+            /// The doc comment, one entry per line.
+            pub docs: &'static [&'static str],
+            /// The `#[attribute(...)]`s forwarded to the generated struct, without the `attribute(...)`.
+            pub attributes: &'static [&'static str],
+        }
+        #[doc = #field_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #vis struct #field_info {
+            /// The name of the field, or its position for tuple-like variants.
+            pub name: &'static str,
+            /// The type of the field, as written.
+            pub ty: &'static str,
+            /// The doc comment, one entry per line.
+            pub docs: &'static [&'static str],
+        }
+        impl #ident {
+            #vis const VARIANTS: &'static [#variant_info] = &[#(#infos),*];
+        }
+        #(#consts)*
+    }
+}
+
+/// The methods generated on the enum by `field_access`.
+const FIELD_ACCESS_METHODS: &[&str] = &["field", "field_mut", "set_field"];
+
+/// Generates `field`, `field_mut` and `set_field` on each generated struct with named fields, which
+/// are returned separately to be placed next to the structs, and the `{Enum}FieldError` and the same
+/// methods on the enum dispatching to the active variant.
+fn generate_field_access(
+    vis: &Visibility,
+    struct_vis: &Visibility,
+    ident: &Ident,
+    variants: &[Variant],
+    struct_idents: &[Ident],
+    style: &Style,
+) -> (TokenStream, TokenStream) {
+    let error = format_ident!("{ident}FieldError");
+    let error_doc = format!("The error returned by `set_field` on [`{ident}`] and its variants.");
+    let unknown_message = format!("`{ident}` has no field {{:?}} in the active variant");
+
+    // The `match name { .. }` over the fields of one variant, given an expression for each field.
+    let arms = |fields: &FieldsNamed, access: &dyn Fn(usize, &Ident) -> TokenStream| {
+        let names: Vec<_> = fields
+            .named
+            .iter()
+            .map(|field| field.ident.as_ref().unwrap().unraw().to_string())
+            .collect();
+        let accesses: Vec<_> = fields
+            .named
+            .iter()
+            .enumerate()
+            .map(|(i, field)| access(i, field.ident.as_ref().unwrap()))
+            .collect();
+        let tys: Vec<_> = fields.named.iter().map(|field| &field.ty).collect();
+        let cfgs: Vec<_> = fields
+            .named
+            .iter()
+            .map(|field| Cfg::of(&field.attrs))
+            .collect();
+        let expected = tys.iter().map(|ty| type_string(ty));
+        let get = quote! {
+            match name {
+                #(#cfgs #names => ::core::option::Option::Some(&#accesses),)*
+                _ => ::core::option::Option::None,
+            }
+        };
+        let get_mut = quote! {
+            match name {
+                #(#cfgs #names => ::core::option::Option::Some(&mut #accesses),)*
+                _ => ::core::option::Option::None,
+            }
+        };
+        let set = quote! {
+            match name {
+                #(#cfgs #names => {
+                    #accesses = *value.downcast::<#tys>().map_err(|_| #error::TypeMismatch {
+                        field: #names,
+                        expected: #expected,
+                    })?;
+                    ::core::result::Result::Ok(())
+                })*
+                _ => ::core::result::Result::Err(#error::UnknownField(::std::string::String::from(name))),
+            }
+        };
+        (get, get_mut, set)
+    };
+
+    let named = variants
+        .iter()
+        .zip(struct_idents)
+        .filter_map(|(variant, struct_ident)| match &variant.fields {
+            Fields::Named(fields) => Some((
+                &variant.ident,
+                struct_ident,
+                fields,
+                Cfg::of(&variant.attrs),
+            )),
+            _ => None,
+        });
+    let struct_impls = named.clone().map(|(_, struct_ident, fields, cfg)| {
+        let (get, get_mut, set) = arms(fields, &|_, field| quote!(self.#field));
+        quote! {
+            #cfg
+            impl #struct_ident {
+                #struct_vis fn field(&self, name: &str) -> ::core::option::Option<&dyn ::core::any::Any> {
+                    #get
+                }
+                #struct_vis fn field_mut(&mut self, name: &str) -> ::core::option::Option<&mut dyn ::core::any::Any> {
+                    #get_mut
+                }
+                #struct_vis fn set_field(
+                    &mut self,
+                    name: &str,
+                    value: ::std::boxed::Box<dyn ::core::any::Any>,
+                ) -> ::core::result::Result<(), #error> {
+                    #set
+                }
+            }
+        }
+    });
+
+    let (get_arms, get_mut_arms, set_arms) = match style {
+        Style::Wrap => (
+            named
+                .clone()
+                .map(|(id, _, _, cfg)| quote!(#cfg #ident::#id(variant) => variant.field(name),))
+                .collect(),
+            named
+                .clone()
+                .map(|(id, _, _, cfg)| {
+                    quote!(#cfg #ident::#id(variant) => variant.field_mut(name),)
+                })
+                .collect(),
+            named
+                .clone()
+                .map(|(id, _, _, cfg)| {
+                    quote!(#cfg #ident::#id(variant) => variant.set_field(name, value),)
+                })
+                .collect(),
+        ),
+        // The fields are bound to `__{i}` so that they cannot shadow `name` or `value`.
+        Style::Keep => named.clone().fold(
+            (quote!(), quote!(), quote!()),
+            |(get_arms, get_mut_arms, set_arms), (id, _, fields, cfg)| {
+                let bindings = (0..fields.named.len()).map(|i| format_ident!("__{i}"));
+                let field_names = fields.named.iter().map(|field| &field.ident);
+                let field_cfgs = fields.named.iter().map(|field| Cfg::of(&field.attrs));
+                let pattern =
+                    quote!(#cfg #ident::#id { #(#field_cfgs #field_names: #bindings),* });
+                let (get, get_mut, set) = arms(fields, &|i, _| {
+                    let binding = format_ident!("__{i}");
+                    quote!(*#binding)
+                });
+                (
+                    quote!(#get_arms #pattern => #get,),
+                    quote!(#get_mut_arms #pattern => #get_mut,),
+                    quote!(#set_arms #pattern => #set,),
+                )
+            },
+        ),
+    };
+
+    let enum_impls = quote! {
+        #[doc = #error_doc]
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        #vis enum #error {
+            /// No field has this name.
+            UnknownField(::std::string::String),
+            /// The value given to `set_field` is not of the type of the field.
+            TypeMismatch {
+                /// The name of the field.
+                field: &'static str,
+                /// The type of the field, as written.
+                expected: &'static str,
+            },
+        }
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self {
+                    #error::UnknownField(name) => ::core::write!(f, #unknown_message, name),
+                    #error::TypeMismatch { field, expected } => {
+                        ::core::write!(f, "field `{}` expects a value of type `{}`", field, expected)
+                    }
+                }
+            }
+        }
+        impl ::std::error::Error for #error {}
+
+        impl #ident {
+            #vis fn field(&self, name: &str) -> ::core::option::Option<&dyn ::core::any::Any> {
+                match self {
+                    #get_arms
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::option::Option::None,
+                }
+            }
+            #vis fn field_mut(&mut self, name: &str) -> ::core::option::Option<&mut dyn ::core::any::Any> {
+                match self {
+                    #get_mut_arms
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::option::Option::None,
+                }
+            }
+            #vis fn set_field(
+                &mut self,
+                name: &str,
+                value: ::std::boxed::Box<dyn ::core::any::Any>,
+            ) -> ::core::result::Result<(), #error> {
+                match self {
+                    #set_arms
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::result::Result::Err(#error::UnknownField(::std::string::String::from(name))),
+                }
+            }
+        }
+    };
+    (quote!(#(#struct_impls)*), enum_impls)
+}
+
+/// Generates the `{Struct}Ref<'a>` and `{Struct}Mut<'a>` views over the fields of each non-unit
+/// variant of a "keep" style enum, along with the `as_*_view` and `as_*_view_mut` methods.
+fn generate_views(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[Variant],
+    struct_idents: &[Ident],
+) -> TokenStream {
+    let views = variants
+        .iter()
+        .zip(struct_idents)
+        .filter(|(variant, _)| !variant.fields.is_empty())
+        .map(|(Variant { attrs, ident: id, fields, .. }, struct_ident)| {
+            let cfg = Cfg::of(attrs);
+            let ref_ident = format_ident!("{struct_ident}Ref");
+            let mut_ident = format_ident!("{struct_ident}Mut");
+            let as_view = format_ident!("as_{}_view", snake_ident(id));
+            let as_view_mut = format_ident!("as_{}_view_mut", snake_ident(id));
+            let ref_doc = format!("Borrows the fields of [`{ident}::{id}`], see [`{ident}::{as_view}`].");
+            let mut_doc =
+                format!("Mutably borrows the fields of [`{ident}::{id}`], see [`{ident}::{as_view_mut}`].");
+
+            let docs = fields.iter().map(|field| {
+                field
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("doc") || attr.path().is_ident("cfg"))
+                    .collect::<Vec<_>>()
+            });
+            let tys = fields.iter().map(|field| &field.ty);
+            // The patterns matching the variant, each with its `#[cfg(...)]`, and the fields of the
+            // view built from the bindings.
+            let (ref_def, mut_def, patterns, construct) = match fields {
+                Fields::Named(_) => {
+                    let names: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+                    let cfgs = fields.iter().map(|field| Cfg::of(&field.attrs));
+                    let docs: Vec<_> = docs.collect();
+                    let tys: Vec<_> = tys.collect();
+                    let pattern = quote!({ #(#cfgs #names),* });
+                    (
+                        quote!({ #(#(#docs)* #vis #names: &'a #tys,)* }),
+                        quote!({ #(#(#docs)* #vis #names: &'a mut #tys,)* }),
+                        vec![(quote!(), pattern.clone())],
+                        pattern,
+                    )
+                }
+                Fields::Unnamed(_) => {
+                    let names: Vec<_> = (0..fields.len()).map(|i| format_ident!("_{i}")).collect();
+                    let cfgs: Vec<_> = fields.iter().map(|field| Cfg::of(&field.attrs)).collect();
+                    let docs: Vec<_> = docs.collect();
+                    let tys: Vec<_> = tys.collect();
+                    // Tuple patterns cannot hold `#[cfg(...)]`s, so there is one per combination
+                    // of enabled fields.
+                    let patterns = cfg_combinations(&cfgs)
+                        .into_iter()
+                        .map(|(cfg, enabled)| {
+                            let names = names.iter().zip(enabled).filter(|(_, on)| *on);
+                            let names = names.map(|(name, _)| name);
+                            (cfg, quote!((#(#names),*)))
+                        })
+                        .collect();
+                    (
+                        quote!((#(#(#docs)* #vis &'a #tys),*);),
+                        quote!((#(#(#docs)* #vis &'a mut #tys),*);),
+                        patterns,
+                        quote!((#(#cfgs #names),*)),
+                    )
+                }
+                Fields::Unit => unreachable!(),
+            };
+            let (pattern_cfgs, patterns): (Vec<_>, Vec<_>) = patterns.into_iter().unzip();
+            quote! {
+                #cfg
+                #[doc = #ref_doc]
+                #[derive(Clone, Copy)]
+                #vis struct #ref_ident<'a> #ref_def
+                #cfg
+                #[doc = #mut_doc]
+                #vis struct #mut_ident<'a> #mut_def
+
+                #cfg
+                impl #ident {
+                    #vis fn #as_view(&self) -> ::core::option::Option<#ref_ident<'_>> {
+                        match self {
+                            #(
+                                #pattern_cfgs
+                                #ident::#id #patterns => {
+                                    ::core::option::Option::Some(#ref_ident #construct)
+                                }
+                            )*
+                            #[allow(unreachable_patterns)]
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+                    #vis fn #as_view_mut(&mut self) -> ::core::option::Option<#mut_ident<'_>> {
+                        match self {
+                            #(
+                                #pattern_cfgs
+                                #ident::#id #patterns => {
+                                    ::core::option::Option::Some(#mut_ident #construct)
+                                }
+                            )*
+                            #[allow(unreachable_patterns)]
+                            _ => ::core::option::Option::None,
+                        }
+                    }
+                }
+            }
+        });
+    quote!(#(#views)*)
+}
+
+/// Generates `field(&self)` and `field_mut(&mut self)` on the enum for the named fields shared by
+/// several variants with the same type, and for the `shared` fields. They return references when
+/// every variant has the field, and [`Option`]s otherwise. Also returns the names of those methods.
+///
+/// Inferred fields whose accessors would take a name in `taken` are left out, fields given by name
+/// are reported.
+fn generate_common(
+    vis: &Visibility,
+    ident: &Ident,
+    variants: &[Variant],
+    style: &Style,
+    common: Option<&Common>,
+    shared: &[Ident],
+    taken: &[String],
+) -> Result<(TokenStream, Vec<String>)> {
+    // Every named field, in order of first appearance, with the variants holding it.
+    let mut fields: Vec<(&Ident, Vec<(usize, &Field)>)> = Vec::new();
+    for (i, variant) in variants.iter().enumerate() {
+        for field in &variant.fields {
+            let Some(name) = &field.ident else { continue };
+            match fields.iter_mut().find(|(other, _)| *other == name) {
+                Some((_, holders)) => holders.push((i, field)),
+                None => fields.push((name, vec![(i, field)])),
+            }
+        }
+    }
+    let mismatched_type = |holders: &[(usize, &Field)]| {
+        holders
+            .iter()
+            .find(|(_, field)| type_string(&field.ty) != type_string(&holders[0].1.ty))
+            .map(|(_, field)| field.ty.clone())
+    };
+    let accessors_of = |name: &Ident| [name.to_string(), format!("{}_mut", name.unraw())];
+    let is_taken = |name: &Ident| {
+        accessors_of(name)
+            .iter()
+            .any(|accessor| taken.contains(accessor))
+    };
+    let selected: Vec<_> = match common {
+        Some(Common::Inferred) => fields
+            .into_iter()
+            .filter(|(name, holders)| {
+                shared.contains(name)
+                    || holders.len() > 1 && mismatched_type(holders).is_none() && !is_taken(name)
+            })
+            .collect(),
+        Some(Common::Fields(names)) => names
+            .iter()
+            .chain(shared.iter().filter(|name| !names.contains(name)))
+            .map(|name| {
+                let Some((_, holders)) = fields.iter().find(|(field, _)| *field == name) else {
+                    Err(Error::new_spanned(
+                        name,
+                        "no variant has a named field with this name",
+                    ))?
+                };
+                if let Some(ty) = mismatched_type(holders) {
+                    Err(Error::new_spanned(
+                        ty,
+                        format!("`{name}` has a different type in another variant"),
+                    ))?
+                }
+                Ok((name, holders.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => fields
+            .into_iter()
+            .filter(|(name, _)| shared.contains(name))
+            .collect(),
+    };
+
+    // What is left are the names given in `common(...)` or `shared(...)`, with their spans.
+    if let Some((name, _)) = selected.iter().find(|(name, _)| is_taken(name)) {
+        Err(Error::new_spanned(
+            name,
+            format!("the accessors of `{name}` conflict with another method of `{ident}`"),
+        ))?
+    }
+    let methods = selected
+        .iter()
+        .flat_map(|(name, _)| accessors_of(name))
+        .collect();
+    let accessors = selected.iter().map(|(name, holders)| {
+        let ty = &holders[0].1.ty;
+        let name_mut = format_ident!("{}_mut", name.unraw());
+        // Fields with `#[cfg(...)]` may be missing, so they are only everywhere without one.
+        let everywhere = holders.len() == variants.len()
+            && holders
+                .iter()
+                .all(|(_, field)| Cfg::of(&field.attrs).is_empty());
+        let arms = |binding: TokenStream, borrow: TokenStream| {
+            holders.iter().map(move |(i, field)| {
+                let id = &variants[*i].ident;
+                let variant_cfg = Cfg::of(&variants[*i].attrs);
+                let field_cfg = Cfg::of(&field.attrs);
+                let (pattern, value) = match style {
+                    Style::Wrap => (
+                        quote!(#ident::#id(#binding value)),
+                        quote!(#borrow value.#name),
+                    ),
+                    Style::Keep => (quote!(#ident::#id { #binding #name, .. }), quote!(#name)),
+                };
+                let value = if everywhere {
+                    value
+                } else {
+                    quote!(::core::option::Option::Some(#value))
+                };
+                quote!(#variant_cfg #field_cfg #pattern => #value,)
+            })
+        };
+        let (get_arms, get_mut_arms) = (
+            arms(quote!(ref), quote!(&)),
+            arms(quote!(ref mut), quote!(&mut)),
+        );
+        let (get_ty, get_mut_ty, fallback) = if everywhere {
+            (quote!(&#ty), quote!(&mut #ty), quote!())
+        } else {
+            (
+                quote!(::core::option::Option<&#ty>),
+                quote!(::core::option::Option<&mut #ty>),
+                quote! {
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::option::Option::None,
+                },
+            )
+        };
+        quote! {
+            #vis fn #name(&self) -> #get_ty {
+                match *self {
+                    #(#get_arms)*
+                    #fallback
+                }
+            }
+            #vis fn #name_mut(&mut self) -> #get_mut_ty {
+                match *self {
+                    #(#get_mut_arms)*
+                    #fallback
+                }
+            }
+        }
+    });
+    let accessors = quote! {
+        impl #ident {
+            #(#accessors)*
+        }
+    };
+    Ok((accessors, methods))
+}
+
 #[derive(Default)]
 enum Style {
     /// Extract the fields definition out to a generated struct
@@ -198,31 +1649,195 @@ enum Style {
     /// identical the variant.
     Keep,
 }
+impl Style {
+    /// Rejects options that need each variant to hold exactly one value.
+    fn require_wrap(&self, param: impl ToTokens, name: &str) -> Result<()> {
+        match self {
+            Style::Wrap => Ok(()),
+            Style::Keep => Err(Error::new_spanned(
+                param,
+                format!(r#"`{name}` requires `style = "wrap"`"#),
+            )),
+        }
+    }
+    /// Rejects options that only make sense when the variants keep their fields inline.
+    fn require_keep(&self, param: impl ToTokens, name: &str) -> Result<()> {
+        match self {
+            Style::Keep => Ok(()),
+            Style::Wrap => Err(Error::new_spanned(
+                param,
+                format!(r#"`{name}` requires `style = "keep"`"#),
+            )),
+        }
+    }
+}
+/// `new` or `new(into)`
+struct NewParam {
+    new: kw::new,
+    into: Option<Parenthesized<kw::into>>,
+}
+impl Parse for NewParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            new: input.parse()?,
+            into: if input.peek(token::Paren) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+        })
+    }
+}
+impl ToTokens for NewParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.new.to_tokens(tokens);
+        self.into.to_tokens(tokens);
+    }
+}
+
+/// `builder` or `builder(into)`
+struct BuilderParam {
+    builder: kw::builder,
+    into: Option<Parenthesized<kw::into>>,
+}
+impl Parse for BuilderParam {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            builder: input.parse()?,
+            into: if input.peek(token::Paren) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+        })
+    }
+}
+impl ToTokens for BuilderParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.builder.to_tokens(tokens);
+        self.into.to_tokens(tokens);
+    }
+}
+
+/// Which fields `common` generates accessors for.
+enum Common {
+    /// `common`: the fields found in several variants with the same type.
+    Inferred,
+    /// `common(field0, field1, ...)`
+    Fields(Vec<Ident>),
+}
+
 struct Config {
     map_ident: Box<dyn Fn(&Ident) -> Ident>,
     implement_conversions: bool,
     style: Style,
     derive_exclude: Vec<Path>,
+    forward_attrs: Vec<Path>,
+    struct_attrs: Vec<Meta>,
+    visitor: Option<VisitorParam>,
+    fold: Option<kw::fold>,
+    borrowed: Option<kw::borrowed>,
+    views: Option<kw::views>,
+    names: bool,
+    rename_all: Option<RenameAll>,
+    tag: bool,
+    from_str: Option<kw::from_str>,
+    reflect: bool,
+    field_access: bool,
+    iter: Option<IterParam>,
+    module: Option<Module>,
+    field_vis: Option<FieldVisParam>,
+    default: Option<DefaultParam>,
+    wrap_existing: Option<kw::wrap_existing>,
+    box_above: Option<BoxAbove>,
+    max_size: Option<MaxSize>,
+    size_report: Option<kw::size_report>,
+    require: Option<Require>,
+    common: Option<Common>,
+    shared: Vec<Field>,
+    new: Option<NewParam>,
+    tuple: bool,
+    constructors: bool,
+    builder: Option<BuilderParam>,
 }
 impl Config {
     fn new(
         Params {
             prefix,
             suffix,
+            name,
             no_impl,
             simplify,
             variant_style,
             derive_exclude,
+            forward_attrs,
+            struct_attrs,
+            visitor,
+            fold,
+            borrowed,
+            views,
+            names,
+            rename_all,
+            tag,
+            from_str,
+            reflect,
+            field_access,
+            iter,
+            module,
+            field_vis,
+            default,
+            wrap_existing,
+            box_above,
+            max_size,
+            size_report,
+            require,
+            common,
+            shared,
+            new,
+            tuple,
+            constructors,
+            builder,
         }: Params,
         item_enum: &ItemEnum,
     ) -> Self {
         let prefix = prefix.unwrap_or_default();
         let suffix = suffix.unwrap_or_default();
+        let enum_ident = item_enum.ident.clone();
         Self {
-            map_ident: Box::new(move |vid| format_ident!("{prefix}{vid}{suffix}")),
+            map_ident: match name {
+                Some(name) => Box::new(move |vid| name.render(&enum_ident, vid)),
+                None => Box::new(move |vid| format_ident!("{prefix}{vid}{suffix}")),
+            },
             implement_conversions: !no_impl.unwrap_or_default(),
             style: variant_style.unwrap_or_default(),
             derive_exclude,
+            forward_attrs,
+            struct_attrs,
+            visitor,
+            fold,
+            borrowed,
+            views,
+            names: names.is_some(),
+            rename_all,
+            tag: tag.is_some(),
+            from_str,
+            reflect: reflect.is_some(),
+            field_access: field_access.is_some(),
+            iter,
+            module,
+            field_vis,
+            default,
+            wrap_existing,
+            box_above,
+            max_size,
+            size_report,
+            require,
+            common,
+            shared,
+            new,
+            tuple: tuple.is_some(),
+            constructors: constructors.is_some(),
+            builder,
         }
     }
 }
@@ -231,6 +1846,7 @@ impl Config {
 struct Params {
     prefix: Option<String>,
     suffix: Option<String>,
+    name: Option<NameTemplate>,
     no_impl: Option<bool>,
     /// Simplifies the variants in 3 cases depending on the level.
     /// Simplification here means reducing the variant into a unit variant.
@@ -247,6 +1863,36 @@ struct Params {
     simplify: Option<u32>,
     variant_style: Option<Style>,
     derive_exclude: Vec<Path>,
+    /// Paths of the attributes of the enum that are copied to every generated struct.
+    forward_attrs: Vec<Path>,
+    /// Attributes added to every generated struct.
+    struct_attrs: Vec<Meta>,
+    visitor: Option<VisitorParam>,
+    fold: Option<kw::fold>,
+    borrowed: Option<kw::borrowed>,
+    views: Option<kw::views>,
+    names: Option<kw::names>,
+    rename_all: Option<RenameAll>,
+    tag: Option<kw::tag>,
+    from_str: Option<kw::from_str>,
+    reflect: Option<kw::reflect>,
+    field_access: Option<kw::field_access>,
+    iter: Option<IterParam>,
+    module: Option<Module>,
+    field_vis: Option<FieldVisParam>,
+    default: Option<DefaultParam>,
+    wrap_existing: Option<kw::wrap_existing>,
+    box_above: Option<BoxAbove>,
+    max_size: Option<MaxSize>,
+    size_report: Option<kw::size_report>,
+    require: Option<Require>,
+    common: Option<Common>,
+    /// Fields added in front of every generated struct.
+    shared: Vec<Field>,
+    new: Option<NewParam>,
+    tuple: Option<kw::tuple>,
+    constructors: Option<kw::constructors>,
+    builder: Option<BuilderParam>,
     // generic: TODO
 }
 
@@ -313,6 +1959,9 @@ impl TryFrom<Args> for Params {
                         _ => Err(error!(arg))?,
                     })
                 }
+                "field_vis" => params.field_vis = Some(syn::parse2(arg.into_token_stream())?),
+                "module" => params.module = Some(syn::parse2(arg.into_token_stream())?),
+                "name" => params.name = Some(syn::parse2(arg.into_token_stream())?),
                 "no_impl" => params.no_impl = no_impl_value(arg)?,
                 "style" => {
                     macro_rules! error {
@@ -363,12 +2012,97 @@ impl TryFrom<Args> for Params {
                         Err(error!(arg))?
                     }
                 }
+                "forward_attrs" => {
+                    if let Meta::List(MetaList { tokens, .. }) = arg {
+                        let a = Punctuated::<Path, Token![,]>::parse_terminated.parse2(tokens)?;
+                        params.forward_attrs.extend(a)
+                    } else {
+                        Err(Error::new_spanned(
+                            arg,
+                            "valid form is `forward_attrs(Path0, Path1, ...)`",
+                        ))?
+                    }
+                }
+                "struct_attrs" => {
+                    if let Meta::List(MetaList { tokens, .. }) = arg {
+                        let a = Punctuated::<Meta, Token![,]>::parse_terminated.parse2(tokens)?;
+                        params.struct_attrs.extend(a)
+                    } else {
+                        Err(Error::new_spanned(
+                            arg,
+                            "valid form is `struct_attrs(attr0, attr1, ...)`",
+                        ))?
+                    }
+                }
+                "visitor" => params.visitor = Some(syn::parse2(arg.into_token_stream())?),
+                "fold" => params.fold = Some(syn::parse2(arg.into_token_stream())?),
+                "borrowed" => params.borrowed = Some(syn::parse2(arg.into_token_stream())?),
+                "views" => params.views = Some(syn::parse2(arg.into_token_stream())?),
+                "names" => params.names = Some(syn::parse2(arg.into_token_stream())?),
+                "rename_all" => params.rename_all = Some(syn::parse2(arg.into_token_stream())?),
+                "tag" => params.tag = Some(syn::parse2(arg.into_token_stream())?),
+                "from_str" => params.from_str = Some(syn::parse2(arg.into_token_stream())?),
+                "reflect" => params.reflect = Some(syn::parse2(arg.into_token_stream())?),
+                "iter" => params.iter = Some(syn::parse2(arg.into_token_stream())?),
+                "field_access" => params.field_access = Some(syn::parse2(arg.into_token_stream())?),
+                "default" => params.default = Some(syn::parse2(arg.into_token_stream())?),
+                "max_size" => params.max_size = Some(syn::parse2(arg.into_token_stream())?),
+                "size_report" => params.size_report = Some(syn::parse2(arg.into_token_stream())?),
+                "common" => {
+                    params.common = Some(match arg {
+                        Meta::Path(_) => Common::Inferred,
+                        Meta::List(MetaList { tokens, .. }) => Common::Fields(
+                            Punctuated::<Ident, Token![,]>::parse_terminated
+                                .parse2(tokens)?
+                                .into_iter()
+                                .collect(),
+                        ),
+                        Meta::NameValue(_) => Err(Error::new_spanned(
+                            arg,
+                            "valid forms are `common` or `common(field0, field1, ...)`",
+                        ))?,
+                    })
+                }
+                "builder" => params.builder = Some(syn::parse2(arg.into_token_stream())?),
+                "constructors" => params.constructors = Some(syn::parse2(arg.into_token_stream())?),
+                "new" => params.new = Some(syn::parse2(arg.into_token_stream())?),
+                "tuple" => params.tuple = Some(syn::parse2(arg.into_token_stream())?),
+                "shared" => {
+                    if let Meta::List(MetaList { tokens, .. }) = arg {
+                        let parser = |input: ParseStream| {
+                            Punctuated::<Field, Token![,]>::parse_terminated_with(
+                                input,
+                                Field::parse_named,
+                            )
+                        };
+                        let a = parser.parse2(tokens)?;
+                        params.shared.extend(a)
+                    } else {
+                        Err(Error::new_spanned(
+                            arg,
+                            "valid form is `shared(field0: Type0, field1: Type1, ...)`",
+                        ))?
+                    }
+                }
+                "require" => params.require = Some(syn::parse2(arg.into_token_stream())?),
+                "box_above" => params.box_above = Some(syn::parse2(arg.into_token_stream())?),
+                "wrap_existing" => {
+                    params.wrap_existing = Some(syn::parse2(arg.into_token_stream())?)
+                }
                 _ => Err(Error::new_spanned(
                     ident,
                     "variant_wrapper: unrecognized parameter",
                 ))?,
             }
         }
+        if let Some(name) = &params.name {
+            if params.prefix.is_some() || params.suffix.is_some() {
+                Err(Error::new_spanned(
+                    name,
+                    "`name` cannot be combined with `prefix` or `suffix`",
+                ))?
+            }
+        }
         Ok(params)
     }
 }
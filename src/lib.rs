@@ -29,9 +29,121 @@ fn result_of(doit: Result<impl Into<TokenStream>>) -> TokenStream {
 /// - `no_impl`: stop [`From`] variant and [`TryFrom`] enum from being implemented.
 /// - `prefix`: prepend to the identifier of every generated structs.
 /// - `suffix`: append to the identifier of every generated structs.
+/// - `name`: a template for the identifier of every generated structs, eg. `name = "{Enum}{Variant}Payload"`.
+///   `{Enum}` and `{Variant}` are replaced as written, `{enum}` and `{variant}` in snake_case and `{ENUM}`
+///   and `{VARIANT}` in SCREAMING_SNAKE_CASE. Cannot be combined with `prefix` or `suffix`.
+/// - `module`: `module = name` or `module(name)`, put every generated structs in a `mod name` with the
+///   visibility of the enum, so that they are referred to as `name::Struct`. The structs are made visible
+///   from the same places as the enum.
+/// - `field_vis`: `field_vis(pub)`, `field_vis(pub(crate))`, `field_vis(inherit)` or the same as a string,
+///   eg. `field_vis = "pub(crate)"`, the visibility of the fields of the generated structs. Defaults to
+///   `inherit`, which gives them the visibility of the struct, and so of the enum.
 /// - `style`: affect the enum itself, can be one of two values
 ///     - "wrapped": the default, each enum variant is a tuple holding the generated type
 ///     - "keep": each enum variant is exactly the same as the generated type (more inconvenient)
+/// - `forward_attrs(Path0, Path1, ...)`: copy the attributes of the enum with these paths to every
+///   generated structs, eg. `forward_attrs(serde, allow)`. Derives are always copied.
+/// - `struct_attrs(attr0, attr1, ...)`: add these attributes to every generated structs, eg.
+///   `struct_attrs(derive(Default), repr(C))`.
+/// - `visitor`: generate the `{Enum}Visitor`, `{Enum}VisitorMut` and `{Enum}VisitorOwned` traits,
+///   with one `visit_*` method per variant, and the `accept`, `accept_mut` and `accept_owned` methods.
+///   `visitor(default)` makes every `visit_*` method fall back to a required `visit_other`, so no
+///   variant can then be named `Other`.
+/// - `fold`: generate `fold`, `fold_ref` and `fold_mut`, taking one closure per variant, and
+///   `folder`, `folder_ref` and `folder_mut`, which return an `{Enum}Fold` builder with one setter per
+///   variant whose `finish` only compiles once every arm is given, so no variant can be named `Finish`.
+/// - `borrowed`: generate the `{Enum}Ref<'a>` and `{Enum}Mut<'a>` enums, holding a reference to each
+///   payload, with `as_ref`/`as_mut` on the enum and `cloned`/`to_owned` back when every payload is
+///   [`Clone`].
+/// - `views`: only for "keep" style, generate the `{Struct}Ref<'a>` and `{Struct}Mut<'a>` structs borrowing
+///   the fields of each non-unit variant, and the `as_{variant}_view`/`as_{variant}_view_mut` methods
+///   returning them.
+/// - `names`: generate `variant_name(&self) -> &'static str` on the enum and `const NAME: &'static str`
+///   on each generated struct, but not on the types of `existing` variants.
+/// - `rename_all`: how variant names are derived from their identifiers, one of "snake_case",
+///   "kebab-case" or "SCREAMING_SNAKE_CASE". Without it, names are the identifiers as written.
+/// - `tag`: generate the fieldless `{Enum}Tag` enum and `tag(&self)` on the enum. The tag implements
+///   [`Display`](std::fmt::Display) and [`FromStr`](std::str::FromStr) using the variant names, and
+///   `{Enum}Tag::ALL` holds every tag in declaration order.
+/// - `from_str`: implement [`FromStr`](std::str::FromStr) for the enum, default-constructing the payload
+///   of the named variant.
+/// - `reflect`: generate the `{Enum}VariantInfo` and `{Enum}FieldInfo` structs, `const VARIANTS` on
+///   the enum describing every variant (name, index, generated struct, fields with their types, doc
+///   comments and `#[attribute(...)]`s) and `const VARIANT` on each generated struct.
+/// - `field_access`: generate `field(&self, name)` and `field_mut(&mut self, name)`, returning the
+///   field as [`Any`](std::any::Any), and `set_field(&mut self, name, Box<dyn Any>)` on each struct with
+///   named fields, and the same methods on the enum dispatching to the active variant. `set_field`
+///   fails with an `{Enum}FieldError` on unknown names or mismatched types. Fields must be `'static`.
+/// - `iter`: generate `const VARIANT_COUNT: usize` and `variant_index(&self) -> usize` on the enum.
+///   `iter(default)` also generates `all_default()`, iterating over every variant built with default
+///   values, and `next(&self)`/`prev(&self)`, cycling through them. Payloads have to be [`Default`],
+///   except for variants without fields.
+/// - `default = Variant`: implement [`Default`] for the enum, building this variant. `#[default]` on a
+///   variant does the same. The attribute is removed and `Default` is taken out of the enum's derives,
+///   the payload is built with its own `Default`, which is derived on the generated struct if missing.
+/// - `wrap_existing`: treat every tuple-like variant with exactly 1 field as holding an existing type,
+///   like the `existing` variant option. Only with "wrap" style.
+/// - `box_above = bytes`: store the payloads larger than `bytes` as `Box<Payload>` in the enum. The
+///   conversions keep taking and returning the payload itself, and the accessors of `borrowed`, `visitor`
+///   and `fold` give references to it. Only with "wrap" style.
+/// - `max_size = bytes`: assert at compile time that each payload, as held by the enum, is at most
+///   `bytes` large. The error points to the offending variant. Also implies `size_report`. Only with
+///   "wrap" style.
+/// - `size_report`: generate `const SIZE_REPORT: &[(&str, usize)]` on the enum, the name of each variant
+///   with the size of its payload as held by the enum, so boxed payloads count as a `Box`. Only with
+///   "wrap" style.
+/// - `require(Bound0, Bound1, ...)`: assert at compile time that every payload implements these bounds,
+///   eg. `require(Send, Sync, Clone, 'static)`. The error points to the offending variant.
+/// - `common(field0, field1, ...)`: generate `field(&self) -> &T` and `field_mut(&mut self) -> &mut T`
+///   on the enum for these named fields, which must have the same type in every variant holding them.
+///   When some variants do not have the field, or only under `#[cfg(...)]`, they return `Option<&T>`
+///   and `Option<&mut T>` instead. `common` alone does the same for every field found in several
+///   variants with the same type, except those whose accessors would take the name of another
+///   generated method, eg. `tag` with `tag`. Given by name, such a field is an error.
+/// - `shared(field0: Type0, field1: Type1, ...)`: add these fields in front of every generated struct,
+///   with the accessors of `common` on the enum. Unit and tuple-like variants get named fields, their
+///   fields being named `_0`, `_1`, ... Variants holding an `existing` type are left as they are.
+/// - `new`: generate `new(field0, field1, ...) -> Self` on each generated struct. `new(into)` takes
+///   `impl Into<T>` arguments instead. Fields with `#[cfg(...)]` are only taken when enabled.
+/// - `tuple`: generate `From<(T0, T1, ...)>` on each generated struct, and `into_tuple(self)` on
+///   tuple-like structs or `into_parts(self)` on structs with named fields, returning the fields as a
+///   tuple. Not generated for structs with `#[cfg(...)]` on some fields.
+/// - `constructors`: generate a constructor on the enum for each variant, named after the `snake_case`
+///   form of the variant, or of its `rename`, eg. `MyEnum::a(x, y)` for `A { x, y }`. It takes the fields
+///   of the variant, or the payload itself for `existing` variants. A variant whose constructor would take
+///   the name of another generated method, eg. `Tag` with `tag`, is an error.
+/// - `builder`: generate a builder for each generated struct with named fields, eg. `ABuilder` from
+///   `A::builder()`, with a setter per field taking its type, or `impl Into<T>` with `builder(into)`.
+///   Its `build()` returns `Result<A, MyEnumBuildError>`, failing with the names of the fields that are
+///   neither set nor have a `default`, and `build_enum()` returns the enum variant instead. Fields cannot
+///   be named `build`, `build_enum` or `builder`.
+///
+/// Valid variant options, given as `#[attribute(option, ...)]` and removed from the output:
+/// - `name = "..."`: override the name of the variant.
+/// - `rename = Ident`: override the identifier of the generated struct, only for [`macro@extract_variant`].
+/// - `vis = pub(...)` or `vis = inherit`: override the visibility of the generated struct, only for
+///   [`macro@extract_variant`].
+/// - `no_inherit_derive`: do not give the enum's derives to the generated struct, only for
+///   [`macro@extract_variant`].
+/// - `inherit_derive(Path0, Path1, ...)`: only give these of the enum's derives to the generated struct,
+///   only for [`macro@extract_variant`].
+/// - `existing`: the single field of this tuple-like variant is an existing type, which is used as the
+///   payload as in [`macro@variant_wrapper`] instead of generating a struct, only for
+///   [`macro@extract_variant`] with "wrap" style. Such types do not get `VARIANT` with `reflect`.
+/// - `boxed`: store the payload as `Box<Payload>` in the enum, see `box_above`.
+///
+/// Valid field options, given the same way on the fields of a variant:
+/// - `vis = pub(...)` or `vis = inherit`: override `field_vis` for this field.
+/// - `default = expr`: the value the builder uses when the field is not set, requires `builder`.
+///
+/// Other attributes of the fields end up on both the generated struct and, with "keep" style, the enum,
+/// except `#[attribute(...)]`, which only applies to the struct, and `#[enum_attribute(...)]`, which only
+/// applies to the enum.
+///
+/// `#[cfg(...)]` on variants and fields is repeated on everything generated for them: the structs, the
+/// conversion impls, match arms, trait methods and so on, and counts and indices only include enabled
+/// variants. `#[cfg_attr(...)]` is only copied to the structs. The `folder` builders of `fold` are not
+/// generated when some variants have a `#[cfg(...)]`.
 ///
 /// TODO
 /// - `debug(transparent)`
@@ -55,6 +167,17 @@ pub fn extract_variant(args: TokenStream, input: TokenStream) -> TokenStream {
 /// variant type(s) defined as struct(s).
 ///
 /// The primary purpose of this macro is to create [`From`] and [`TryFrom`] implementations.
+///
+/// Valid arguments:
+/// - `no_impl`: stop [`From`] variant and [`TryFrom`] enum from being implemented.
+/// - `visitor`, `fold`, `borrowed`, `names`, `rename_all`, `tag`, `from_str`, `iter`, `default`,
+///   `box_above`, `max_size`, `size_report`, `require`, `constructors`: same as in
+///   [`macro@extract_variant`].
+///
+/// The constructors of `constructors` take the payload. With `names`, `NAME` is implemented on each
+/// wrapped type, so those have to be local to the crate.
+///
+/// Valid variant options are the same as in [`macro@extract_variant`].
 #[proc_macro_attribute]
 pub fn variant_wrapper(args: TokenStream, input: TokenStream) -> TokenStream {
     result_of(variant_wrapper::doit(
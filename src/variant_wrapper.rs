@@ -8,8 +8,14 @@ use syn::{
 };
 
 use crate::common::{
-    generate_conversion_impl, ident, kw, no_impl_value, optional_attribute_args_list,
-    APIAttributeArgs, AttributeArgs, Eq, NoImpl, WrappedVariant,
+    enabled_methods, generate_borrowed, generate_conversion_impl, generate_default_impl,
+    generate_fold, generate_from_str, generate_iter, generate_names, generate_parse_error,
+    generate_require, generate_size_assertions, generate_size_report, generate_storage_module,
+    generate_tag, generate_variant_constructors, generate_visitor, ident, kw, no_impl_value,
+    optional_attribute_args_list, remove_default_derive, take_default_variant, variant_names,
+    APIAttributeArgs, AttributeArgs, BoxAbove, DefaultParam, Eq, IterParam, MaxSize, NoImpl,
+    RenameAll, Require, Storage, VariantOptions, VisitorParam, WrappedVariant, BORROWED_METHODS,
+    FOLD_METHODS, ITER_DEFAULT_METHODS, ITER_METHODS, NAMES_METHODS, TAG_METHODS, VISITOR_METHODS,
 };
 
 type Params = Punctuated<Param, Token![,]>;
@@ -23,6 +29,20 @@ pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
     let options = Options::try_from(params)?; // TODO - this naming scheme is really stupid, should probabably change it some day
     let Config {
         implement_conversion,
+        visitor,
+        fold,
+        borrowed,
+        names,
+        rename_all,
+        tag,
+        from_str,
+        iter,
+        default,
+        box_above,
+        max_size,
+        size_report,
+        require,
+        constructors,
     } = Config::new(options);
 
     let ItemEnum {
@@ -35,30 +55,161 @@ pub fn doit(args: TokenStream, item_enum: ItemEnum) -> Result<TokenStream> {
         variants,
     } = &item_enum;
 
-    let wrapped_variants: Vec<WrappedVariant> =
-        item_enum.variants.iter().map(wrap_variant).try_collect()?;
+    let mut variants: Vec<Variant> = variants.iter().cloned().collect();
+    let default_variant = take_default_variant(
+        default.as_ref(),
+        variants
+            .iter_mut()
+            .map(|variant| (&variant.ident, &mut variant.attrs)),
+    )?;
+    let mut enum_attrs = attrs.clone();
+    if default_variant.is_some() {
+        remove_default_derive(&mut enum_attrs)?;
+    }
 
-    let conversion_impls = wrapped_variants
+    let wrapped_variants: Vec<WrappedVariant> = variants
         .iter()
-        .map(|WrappedVariant { attrs: _, id, ty }| {
-            if implement_conversion {
-                generate_conversion_impl(ident, id, ty)
-            } else {
-                quote!()
-            }
-        });
+        .map(|variant| wrap_variant(ident, variant, box_above.as_ref()))
+        .try_collect()?;
+
+    let conversion_impls = wrapped_variants.iter().map(|variant| {
+        if implement_conversion {
+            generate_conversion_impl(ident, variant)
+        } else {
+            quote!()
+        }
+    });
+
+    // The methods generated on the enum, which `constructors` cannot take the names of.
+    let iter_default = iter.as_ref().is_some_and(IterParam::has_default);
+    let taken = enabled_methods(&[
+        (visitor.is_some(), VISITOR_METHODS),
+        (fold, FOLD_METHODS),
+        (borrowed, BORROWED_METHODS),
+        (names, NAMES_METHODS),
+        (tag, TAG_METHODS),
+        (iter.is_some(), ITER_METHODS),
+        (iter_default, ITER_DEFAULT_METHODS),
+    ]);
+    let visitor_impl = visitor
+        .map(|visitor| generate_visitor(vis, ident, &wrapped_variants, &visitor))
+        .transpose()?
+        .unwrap_or_default();
+    let fold_impl = if fold {
+        generate_fold(vis, ident, &wrapped_variants)?
+    } else {
+        quote!()
+    };
+    let borrowed_impl = if borrowed {
+        generate_borrowed(vis, ident, &wrapped_variants)
+    } else {
+        quote!()
+    };
+
+    let variant_names = variant_names(&wrapped_variants, rename_all.as_ref());
+    let names_impl = if names {
+        let existing = vec![false; wrapped_variants.len()];
+        generate_names(vis, ident, &wrapped_variants, &existing, &variant_names)
+    } else {
+        quote!()
+    };
+    let parse_error = if tag || from_str {
+        generate_parse_error(vis, ident)
+    } else {
+        quote!()
+    };
+    let tag_impl = if tag {
+        generate_tag(vis, ident, &wrapped_variants, &variant_names)
+    } else {
+        quote!()
+    };
+    let from_str_impl = if from_str {
+        generate_from_str(ident, &wrapped_variants, &variant_names)
+    } else {
+        quote!()
+    };
+    let iter_impl = iter
+        .map(|iter| {
+            let constructors = iter.has_default().then(|| {
+                wrapped_variants
+                    .iter()
+                    .map(|variant @ WrappedVariant { id, ty, .. }| {
+                        let value =
+                            variant.store(quote!(<#ty as ::core::default::Default>::default()));
+                        quote!(#ident::#id(#value))
+                    })
+                    .collect::<Vec<_>>()
+            });
+            generate_iter(vis, ident, &wrapped_variants, constructors.as_deref())
+        })
+        .unwrap_or_default();
+    let default_impl = default_variant
+        .map(|i| {
+            let variant @ WrappedVariant { id, ty, .. } = &wrapped_variants[i];
+            let value = variant.store(quote!(<#ty as ::core::default::Default>::default()));
+            generate_default_impl(ident, quote!(#ident::#id(#value)))
+        })
+        .unwrap_or_default();
+    let size_assertions = max_size
+        .map(|max_size| generate_size_assertions(ident, &wrapped_variants, &max_size))
+        .unwrap_or_default();
+    let size_report_impl = if size_report {
+        generate_size_report(vis, ident, &wrapped_variants)
+    } else {
+        quote!()
+    };
+    let require_impl = require
+        .map(|require| generate_require(&wrapped_variants, &require))
+        .unwrap_or_default();
+    let constructors_impl = if constructors {
+        let constructors = wrapped_variants
+            .iter()
+            .map(|variant @ WrappedVariant { id, ty, .. }| {
+                let value = variant.store(quote!(value));
+                (quote!(value: #ty), quote!(#ident::#id(#value)))
+            })
+            .collect();
+        generate_variant_constructors(vis, ident, &wrapped_variants, constructors, &taken)?
+    } else {
+        quote!()
+    };
+    let storage_module = if box_above.is_some() {
+        generate_storage_module(vis, ident)
+    } else {
+        quote!()
+    };
 
     Ok(quote! {
-        #(#attrs)*
+        #(#enum_attrs)*
         #vis #enum_token #ident {
             #(#wrapped_variants),*
         }
+        #storage_module
         #(#conversion_impls)*
+        #visitor_impl
+        #fold_impl
+        #borrowed_impl
+        #names_impl
+        #parse_error
+        #tag_impl
+        #from_str_impl
+        #iter_impl
+        #default_impl
+        #size_assertions
+        #size_report_impl
+        #require_impl
+        #constructors_impl
     })
 }
 
-fn wrap_variant(variant: &Variant) -> Result<WrappedVariant> {
-    let attrs = variant.attrs.clone();
+fn wrap_variant(
+    ident: &Ident,
+    variant: &Variant,
+    box_above: Option<&BoxAbove>,
+) -> Result<WrappedVariant> {
+    let mut attrs = variant.attrs.clone();
+    let options = VariantOptions::take(&mut attrs)?;
+    options.require_generated_struct()?;
     let id = variant.ident.clone();
     let ty = match &variant.fields {
         Fields::Named(named_fields) => Err(Error::new(
@@ -82,17 +233,66 @@ fn wrap_variant(variant: &Variant) -> Result<WrappedVariant> {
             path: Path::from(id.clone()),
         }),
     };
-    Ok(WrappedVariant { attrs, id, ty })
+    let storage = Storage::new(ident, &options, box_above);
+    Ok(WrappedVariant {
+        attrs,
+        id,
+        ty,
+        options,
+        storage,
+    })
 }
 
 enum Param {
     NoImpl(NoImpl),
+    Visitor(VisitorParam),
+    Fold(kw::fold),
+    Borrowed(kw::borrowed),
+    Names(kw::names),
+    RenameAll(RenameAll),
+    Tag(kw::tag),
+    FromStr(kw::from_str),
+    Iter(IterParam),
+    Default(DefaultParam),
+    BoxAbove(BoxAbove),
+    MaxSize(MaxSize),
+    SizeReport(kw::size_report),
+    Require(Require),
+    Constructors(kw::constructors),
 }
 impl Parse for Param {
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
         if lookahead.peek(kw::no_impl) {
             Ok(Param::NoImpl(input.parse()?))
+        } else if lookahead.peek(kw::visitor) {
+            Ok(Param::Visitor(input.parse()?))
+        } else if lookahead.peek(kw::fold) {
+            Ok(Param::Fold(input.parse()?))
+        } else if lookahead.peek(kw::borrowed) {
+            Ok(Param::Borrowed(input.parse()?))
+        } else if lookahead.peek(kw::names) {
+            Ok(Param::Names(input.parse()?))
+        } else if lookahead.peek(kw::rename_all) {
+            Ok(Param::RenameAll(input.parse()?))
+        } else if lookahead.peek(kw::tag) {
+            Ok(Param::Tag(input.parse()?))
+        } else if lookahead.peek(kw::from_str) {
+            Ok(Param::FromStr(input.parse()?))
+        } else if lookahead.peek(kw::iter) {
+            Ok(Param::Iter(input.parse()?))
+        } else if lookahead.peek(kw::default) {
+            Ok(Param::Default(input.parse()?))
+        } else if lookahead.peek(kw::box_above) {
+            Ok(Param::BoxAbove(input.parse()?))
+        } else if lookahead.peek(kw::max_size) {
+            Ok(Param::MaxSize(input.parse()?))
+        } else if lookahead.peek(kw::size_report) {
+            Ok(Param::SizeReport(input.parse()?))
+        } else if lookahead.peek(kw::require) {
+            Ok(Param::Require(input.parse()?))
+        } else if lookahead.peek(kw::constructors) {
+            Ok(Param::Constructors(input.parse()?))
         } else {
             Err(lookahead.error())
         }
@@ -114,11 +314,57 @@ fn fill_empty_or_else<T>(
 // #[derive(FromMeta)]
 struct Config {
     implement_conversion: bool,
+    visitor: Option<VisitorParam>,
+    fold: bool,
+    borrowed: bool,
+    names: bool,
+    rename_all: Option<RenameAll>,
+    tag: bool,
+    from_str: bool,
+    iter: Option<IterParam>,
+    default: Option<DefaultParam>,
+    box_above: Option<BoxAbove>,
+    max_size: Option<MaxSize>,
+    size_report: bool,
+    require: Option<Require>,
+    constructors: bool,
 }
 impl Config {
-    fn new(Options { no_impl }: Options) -> Self {
+    fn new(
+        Options {
+            no_impl,
+            visitor,
+            fold,
+            borrowed,
+            names,
+            rename_all,
+            tag,
+            from_str,
+            iter,
+            default,
+            box_above,
+            max_size,
+            size_report,
+            require,
+            constructors,
+        }: Options,
+    ) -> Self {
         Self {
             implement_conversion: !no_impl.map_or(false, |a| a.truthy()),
+            visitor,
+            fold: fold.is_some(),
+            borrowed: borrowed.is_some(),
+            names: names.is_some(),
+            rename_all,
+            tag: tag.is_some(),
+            from_str: from_str.is_some(),
+            iter,
+            default,
+            box_above,
+            size_report: max_size.is_some() || size_report.is_some(),
+            max_size,
+            require,
+            constructors: constructors.is_some(),
         }
     }
 }
@@ -126,6 +372,20 @@ impl Config {
 #[derive(Default)]
 struct Options {
     no_impl: Option<NoImpl>,
+    visitor: Option<VisitorParam>,
+    fold: Option<kw::fold>,
+    borrowed: Option<kw::borrowed>,
+    names: Option<kw::names>,
+    rename_all: Option<RenameAll>,
+    tag: Option<kw::tag>,
+    from_str: Option<kw::from_str>,
+    iter: Option<IterParam>,
+    default: Option<DefaultParam>,
+    box_above: Option<BoxAbove>,
+    max_size: Option<MaxSize>,
+    size_report: Option<kw::size_report>,
+    require: Option<Require>,
+    constructors: Option<kw::constructors>,
 }
 impl TryFrom<Params> for Options {
     type Error = Error;
@@ -138,6 +398,68 @@ impl TryFrom<Params> for Options {
                         Error::new_spanned(new, "duplicate parameter")
                     })?
                 }
+                Param::Visitor(visitor) => {
+                    fill_empty_or_else(&mut options.visitor, visitor, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::Fold(fold) => fill_empty_or_else(&mut options.fold, fold, |_, new| {
+                    Error::new_spanned(new, "duplicate parameter")
+                })?,
+                Param::Borrowed(borrowed) => {
+                    fill_empty_or_else(&mut options.borrowed, borrowed, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::Names(names) => fill_empty_or_else(&mut options.names, names, |_, new| {
+                    Error::new_spanned(new, "duplicate parameter")
+                })?,
+                Param::RenameAll(rename_all) => {
+                    fill_empty_or_else(&mut options.rename_all, rename_all, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::Tag(tag) => fill_empty_or_else(&mut options.tag, tag, |_, new| {
+                    Error::new_spanned(new, "duplicate parameter")
+                })?,
+                Param::FromStr(from_str) => {
+                    fill_empty_or_else(&mut options.from_str, from_str, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::Iter(iter) => fill_empty_or_else(&mut options.iter, iter, |_, new| {
+                    Error::new_spanned(new, "duplicate parameter")
+                })?,
+                Param::Default(default) => {
+                    fill_empty_or_else(&mut options.default, default, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::BoxAbove(box_above) => {
+                    fill_empty_or_else(&mut options.box_above, box_above, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::MaxSize(max_size) => {
+                    fill_empty_or_else(&mut options.max_size, max_size, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::SizeReport(size_report) => {
+                    fill_empty_or_else(&mut options.size_report, size_report, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::Require(require) => {
+                    fill_empty_or_else(&mut options.require, require, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
+                Param::Constructors(constructors) => {
+                    fill_empty_or_else(&mut options.constructors, constructors, |_, new| {
+                        Error::new_spanned(new, "duplicate parameter")
+                    })?
+                }
             }
         }
         Ok(options)
@@ -155,6 +477,20 @@ fn test() {
         quote!(no_impl),
         quote!(no_impl = true),
         quote!(no_impl = false),
+        quote!(visitor),
+        quote!(no_impl, visitor(default)),
+        quote!(visitor, fold),
+        quote!(borrowed),
+        quote!(names, rename_all = "kebab-case", tag, from_str),
+        quote!(iter, tag),
+        quote!(iter(default)),
+        quote!(default = A),
+        quote!(iter(default), default = A),
+        quote!(box_above = 64, borrowed),
+        quote!(max_size = 64),
+        quote!(size_report, box_above = 16),
+        quote!(require(Send, Sync, Clone, 'static)),
+        quote!(constructors, box_above = 8),
     ] {
         let _ = pipeline(input).unwrap();
     }
@@ -0,0 +1,53 @@
+use enum_macros::{extract_variant, variant_wrapper};
+
+#[extract_variant(borrowed)]
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Text { text: String },
+}
+
+fn length(value: ValueRef<'_>) -> usize {
+    match value {
+        ValueRef::Int(int) => int.0.to_string().len(),
+        ValueRef::Text(text) => text.text.len(),
+    }
+}
+
+struct Handle;
+
+#[variant_wrapper(borrowed)]
+enum Resource {
+    Handle,
+    Name(String),
+}
+
+#[test]
+fn as_ref_and_cloned() {
+    let value = Value::from(Text {
+        text: String::from("abc"),
+    });
+    assert_eq!(length(value.as_ref()), 3);
+    assert_eq!(length(ValueRef::from(&value)), 3);
+    assert_eq!(value.as_ref().cloned(), value);
+    assert_eq!(value.as_ref().to_owned(), value);
+}
+
+#[test]
+fn as_mut() {
+    let mut value = Value::from(Int(1));
+    if let ValueMut::Int(int) = value.as_mut() {
+        int.0 += 1;
+    }
+    assert_eq!(value.as_mut().cloned(), Value::from(Int(2)));
+}
+
+#[test]
+fn without_clone() {
+    let mut resource = Resource::from(Handle);
+    assert!(matches!(resource.as_ref(), ResourceRef::Handle(_)));
+    if let ResourceMut::Name(name) = Resource::from(String::new()).as_mut() {
+        name.push('a');
+    }
+    assert!(matches!(resource.as_mut(), ResourceMut::Handle(_)));
+}
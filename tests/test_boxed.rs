@@ -0,0 +1,74 @@
+use std::mem::size_of;
+
+use enum_macros::{extract_variant, variant_wrapper, EnableExtraParameters};
+
+#[extract_variant(borrowed, visitor, fold)]
+#[derive(Debug, Clone, PartialEq, EnableExtraParameters)]
+enum Packet {
+    Empty,
+    #[attribute(boxed)]
+    Large {
+        data: [u8; 256],
+    },
+}
+
+struct Len;
+impl PacketVisitor for Len {
+    type Output = usize;
+    fn visit_empty(&mut self, _: &Empty) -> usize {
+        0
+    }
+    fn visit_large(&mut self, large: &Large) -> usize {
+        large.data.len()
+    }
+}
+
+#[extract_variant(box_above = 32, iter(default), default = Small)]
+#[derive(Debug, Default, PartialEq)]
+enum Frame {
+    Small { byte: u8 },
+    Big { data: [u64; 16] },
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Header([u8; 32]);
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Flag(bool);
+
+#[variant_wrapper(box_above = 16, borrowed, from_str, names)]
+#[derive(Debug, PartialEq)]
+enum Part {
+    Header(Header),
+    Flag(Flag),
+}
+
+#[test]
+fn boxed_variant() {
+    assert!(size_of::<Packet>() <= 2 * size_of::<usize>());
+    let packet = Packet::from(Large { data: [1; 256] });
+    assert_eq!(packet.accept(&mut Len), 256);
+    assert_eq!(packet.fold_ref(|_| 0, |large| large.data[0]), 1);
+    match packet.as_ref() {
+        PacketRef::Large(large) => assert_eq!(large.data[255], 1),
+        PacketRef::Empty(_) => unreachable!(),
+    }
+    assert_eq!(packet.as_ref().cloned(), packet);
+    assert_eq!(Large::try_from(packet).map(|large| large.data[0]), Ok(1));
+}
+
+#[test]
+fn box_above() {
+    assert!(size_of::<Frame>() <= 2 * size_of::<usize>());
+    assert!(size_of::<Part>() <= 2 * size_of::<usize>());
+    assert_eq!(Frame::default(), Frame::Small(Small { byte: 0 }));
+    assert_eq!(Frame::all_default().count(), 2);
+    let frame = Frame::from(Big { data: [7; 16] });
+    assert_eq!(Big::try_from(frame).map(|big| big.data[3]), Ok(7));
+
+    let mut part = Part::from(Flag(true));
+    if let PartMut::Flag(flag) = part.as_mut() {
+        flag.0 = false;
+    }
+    assert_eq!(Flag::try_from(part), Ok(Flag(false)));
+    assert_eq!("Header".parse(), Ok(Part::from(Header::default())));
+}
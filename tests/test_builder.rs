@@ -0,0 +1,88 @@
+use enum_macros::{extract_variant, EnableExtraParameters};
+
+#[extract_variant(builder)]
+#[derive(Debug, PartialEq, EnableExtraParameters)]
+enum Request {
+    Get {
+        url: String,
+        port: u16,
+        #[attribute(default = 30)]
+        timeout: u32,
+    },
+    Ping(u8),
+    Stop,
+}
+
+mod jobs {
+    use enum_macros::{extract_variant, EnableExtraParameters};
+
+    #[extract_variant(builder(into), module(parts), style = "keep", no_impl)]
+    #[derive(Debug, PartialEq, EnableExtraParameters)]
+    pub enum Job {
+        Run {
+            name: String,
+            #[attribute(default = Vec::new())]
+            args: Vec<String>,
+        },
+    }
+}
+
+#[test]
+fn build() {
+    let get = Get::builder()
+        .url(String::from("example.com"))
+        .port(80)
+        .build()
+        .unwrap();
+    assert_eq!(
+        get,
+        Get {
+            url: String::from("example.com"),
+            port: 80,
+            timeout: 30
+        }
+    );
+    let get = Get::builder()
+        .url(String::from("a"))
+        .port(80)
+        .timeout(5)
+        .build()
+        .unwrap();
+    assert_eq!(get.timeout, 5);
+}
+
+#[test]
+fn missing_field() {
+    let error = Get::builder().timeout(5).build().unwrap_err();
+    assert_eq!(error, RequestBuildError::MissingFields(vec!["url", "port"]));
+    assert_eq!(
+        error.to_string(),
+        r#"missing fields ["url", "port"] to build a variant of `Request`"#
+    );
+    let error = Get::builder().port(80).build().unwrap_err();
+    assert_eq!(error, RequestBuildError::MissingFields(vec!["url"]));
+}
+
+#[test]
+fn build_enum() {
+    assert_eq!(
+        Get::builder().url(String::from("a")).port(80).build_enum(),
+        Ok(Request::Get(Get {
+            url: String::from("a"),
+            port: 80,
+            timeout: 30
+        }))
+    );
+    use jobs::{parts::Run, Job, JobBuildError};
+    assert_eq!(
+        Run::builder().name("ls").build_enum(),
+        Ok(Job::Run {
+            name: String::from("ls"),
+            args: Vec::new()
+        })
+    );
+    assert_eq!(
+        Run::builder().build_enum(),
+        Err(JobBuildError::MissingFields(vec!["name"]))
+    );
+}
@@ -0,0 +1,96 @@
+#![allow(dead_code)]
+
+use enum_macros::extract_variant;
+
+#[extract_variant(
+    visitor,
+    fold,
+    borrowed,
+    names,
+    tag,
+    from_str,
+    iter(default),
+    reflect,
+    field_access,
+    struct_attrs(derive(Default))
+)]
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    #[cfg(any())]
+    Disabled { secret: u8 },
+    Key {
+        code: u32,
+        #[cfg(any())]
+        modifiers: u8,
+    },
+    #[cfg(not(any()))]
+    Click(u8),
+}
+
+#[extract_variant(style = "keep", no_impl, views, iter(default), field_access)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    #[cfg(any())]
+    Hidden,
+    Point {
+        x: i32,
+        #[cfg(any())]
+        z: i32,
+    },
+}
+
+#[extract_variant(style = "keep", no_impl, views)]
+#[derive(Debug, PartialEq)]
+enum Values {
+    Mixed(u8, #[cfg(any())] u16, #[cfg(not(any()))] i8, u32),
+}
+
+struct Counter;
+impl EventVisitor for Counter {
+    type Output = u32;
+    fn visit_key(&mut self, key: &Key) -> u32 {
+        key.code
+    }
+    fn visit_click(&mut self, click: &Click) -> u32 {
+        click.0.into()
+    }
+}
+
+#[test]
+fn disabled_variant() {
+    assert_eq!(Event::VARIANT_COUNT, 2);
+    assert_eq!(EventTag::ALL, [EventTag::Key, EventTag::Click]);
+    assert_eq!(Event::VARIANTS.len(), 2);
+    assert_eq!(Click::VARIANT.index, 1);
+
+    let click = Event::from(Click(2));
+    assert_eq!(click.variant_index(), 1);
+    assert_eq!(click.variant_name(), "Click");
+    assert_eq!(click.accept(&mut Counter), 2);
+    assert_eq!(click.fold_ref(|key| key.code, |click| click.0.into()), 2);
+    assert_eq!(click.as_ref().cloned(), click);
+    assert_eq!(click.next(), Event::Key(Key::default()));
+    assert_eq!("Key".parse(), Ok(Event::Key(Key::default())));
+    assert!("Disabled".parse::<Event>().is_err());
+}
+
+#[test]
+fn disabled_field() {
+    let key = Key { code: 1 };
+    assert!(key.field("modifiers").is_none());
+    assert_eq!(Key::VARIANT.fields.len(), 1);
+
+    let point = Shape::Point { x: 1 };
+    assert_eq!(point.as_point_view().map(|view| *view.x), Some(1));
+    assert!(point.field("z").is_none());
+    assert_eq!(Shape::all_default().count(), 1);
+}
+
+#[test]
+fn disabled_tuple_field() {
+    let mut mixed = Values::Mixed(1, -2, 3);
+    let view = mixed.as_mixed_view().unwrap();
+    assert_eq!((*view.0, *view.1, *view.2), (1, -2, 3));
+    *mixed.as_mixed_view_mut().unwrap().2 = 4;
+    assert_eq!(mixed, Values::Mixed(1, -2, 4));
+}
@@ -0,0 +1,66 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(common)]
+#[derive(Debug, PartialEq)]
+enum Envelope {
+    Open {
+        id: u64,
+        sender: String,
+    },
+    Data {
+        id: u64,
+        sender: String,
+        payload: Vec<u8>,
+    },
+    Close {
+        id: u64,
+        code: u16,
+    },
+    Ping,
+}
+
+#[extract_variant(style = "keep", no_impl, common(id, code))]
+#[derive(Debug, PartialEq)]
+enum Frame {
+    Start { id: u64 },
+    End { id: u64, code: u16 },
+}
+
+#[extract_variant(common, tag)]
+#[derive(Debug, PartialEq)]
+enum Packet {
+    Chunk { tag: u8, len: u16 },
+    Ack { tag: u8, len: u16 },
+}
+
+#[test]
+fn inferred() {
+    let mut envelope = Envelope::Data(Data {
+        id: 1,
+        sender: String::from("a"),
+        payload: Vec::new(),
+    });
+    assert_eq!(envelope.id(), Some(&1));
+    assert_eq!(envelope.sender().map(String::as_str), Some("a"));
+    *envelope.id_mut().unwrap() = 2;
+    assert_eq!(envelope.id(), Some(&2));
+    assert_eq!(Envelope::Ping(Ping).id(), None);
+    assert_eq!(Envelope::Close(Close { id: 3, code: 0 }).sender(), None);
+}
+
+#[test]
+fn explicit() {
+    let mut frame = Frame::End { id: 7, code: 1 };
+    assert_eq!(*frame.id(), 7);
+    *frame.id_mut() = 8;
+    assert_eq!(frame, Frame::End { id: 8, code: 1 });
+    assert_eq!(frame.code(), Some(&1));
+    assert_eq!(Frame::Start { id: 0 }.code_mut(), None);
+}
+
+#[test]
+fn inferred_skips_taken_names() {
+    let packet = Packet::Chunk(Chunk { tag: 7, len: 3 });
+    assert_eq!(packet.tag(), PacketTag::Chunk);
+    assert_eq!(*packet.len(), 3);
+}
@@ -0,0 +1,60 @@
+use enum_macros::extract_variant;
+
+mod shapes {
+    use enum_macros::extract_variant;
+
+    #[extract_variant(new(into), tuple, field_vis(pub(self)))]
+    #[derive(Debug, PartialEq)]
+    pub enum Shape {
+        Circle { radius: f64, label: String },
+        Line(i32, i32),
+        Empty,
+    }
+}
+
+#[extract_variant(new, tuple)]
+#[derive(Debug, PartialEq)]
+enum Message {
+    Text { body: String },
+    Point(u8, u8),
+}
+
+#[test]
+fn new() {
+    use shapes::{Circle, Empty, Line, Shape};
+    let circle = Circle::new(1.5, "unit");
+    assert_eq!(circle.into_parts(), (1.5, String::from("unit")));
+    let line = Line::new(1, 2);
+    assert_eq!(Shape::from(line), Shape::Line(Line::from((1, 2))));
+    assert_eq!(Line::new(3, 4).into_tuple(), (3, 4));
+    assert_eq!(Empty::new(), Empty);
+    assert_eq!(
+        Text::new(String::from("hi")),
+        Text {
+            body: String::from("hi")
+        }
+    );
+}
+
+#[test]
+fn tuple() {
+    assert_eq!(Point::from((1, 2)), Point(1, 2));
+    assert_eq!(Point::new(1, 2).into_tuple(), (1, 2));
+    let text = Text::from((String::from("a"),));
+    assert_eq!(text.into_parts(), (String::from("a"),));
+    assert_eq!(
+        Message::from(Point(0, 0)),
+        Message::Point(Point::from((0, 0)))
+    );
+}
+
+#[extract_variant(new)]
+#[derive(Debug, PartialEq)]
+enum Sample {
+    Reading(u8, #[cfg(any())] u16, u32),
+}
+
+#[test]
+fn disabled_field() {
+    assert_eq!(Reading::new(1, 2), Reading(1, 2));
+}
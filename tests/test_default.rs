@@ -0,0 +1,57 @@
+use enum_macros::{extract_variant, variant_wrapper};
+
+#[extract_variant]
+#[derive(Debug, Default, PartialEq)]
+enum Config {
+    #[default]
+    Local,
+    Remote {
+        host: String,
+    },
+}
+
+#[extract_variant(default = Verbose)]
+#[derive(Debug, PartialEq)]
+enum Level {
+    Quiet,
+    Verbose { depth: u8 },
+}
+
+#[extract_variant(style = "keep", no_impl)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Point,
+    #[default]
+    Square(u32),
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Idle;
+#[derive(Debug, PartialEq)]
+struct Busy(u32);
+
+#[variant_wrapper]
+#[derive(Debug, Default, PartialEq)]
+enum State {
+    #[default]
+    Idle(Idle),
+    Busy(Busy),
+}
+
+#[test]
+#[allow(clippy::default_constructed_unit_structs)]
+fn default_variant() {
+    assert_eq!(Config::default(), Config::Local(Local));
+    assert_eq!(Local::default(), Local);
+    assert_eq!(Level::default(), Level::Verbose(Verbose { depth: 0 }));
+    assert_eq!(Shape::default(), Shape::Square(0));
+    assert_eq!(State::default(), State::Idle(Idle));
+    let _ = (
+        Remote {
+            host: String::new(),
+        },
+        Quiet,
+        Shape::Point,
+        Busy(1),
+    );
+}
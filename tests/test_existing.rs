@@ -0,0 +1,77 @@
+use enum_macros::{extract_variant, EnableExtraParameters};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Shared {
+    id: u32,
+}
+
+#[extract_variant(tag)]
+#[derive(Debug, PartialEq, EnableExtraParameters)]
+enum Message {
+    Ping,
+    #[attribute(existing)]
+    Shared(Shared),
+    Text(String),
+}
+
+#[extract_variant(wrap_existing, default = Count, reflect)]
+#[derive(Debug, PartialEq)]
+enum Event {
+    Count(u64),
+    Shared(Shared),
+    Move { x: i32, y: i32 },
+    Pair(u8, u8),
+}
+
+#[extract_variant(names, wrap_existing)]
+#[derive(Debug, PartialEq)]
+enum Note {
+    Shared(Shared),
+    Label(String),
+    Memo { text: String },
+}
+
+#[extract_variant(names, wrap_existing)]
+#[derive(Debug, PartialEq)]
+enum Log {
+    Shared(Shared),
+    Entry { line: u32 },
+}
+
+#[test]
+fn existing_attribute() {
+    let message: Message = Shared { id: 3 }.into();
+    assert_eq!(message, Message::Shared(Shared { id: 3 }));
+    assert_eq!(Shared::try_from(message), Ok(Shared { id: 3 }));
+    assert_eq!(
+        Message::Text(Text(String::from("hi"))).tag(),
+        MessageTag::Text
+    );
+    assert_eq!(Message::from(Ping), Message::Ping(Ping));
+}
+
+#[test]
+fn wrap_existing() {
+    assert_eq!(Event::from(3u64), Event::Count(3));
+    assert_eq!(Event::default(), Event::Count(0));
+    assert_eq!(
+        Event::from(Move { x: 1, y: 2 }),
+        Event::Move(Move { x: 1, y: 2 })
+    );
+    assert_eq!(Event::from(Pair(1, 2)), Event::Pair(Pair(1, 2)));
+    assert_eq!(
+        Event::from(Shared::default()),
+        Event::Shared(Shared { id: 0 })
+    );
+    assert_eq!(Event::VARIANTS[1].struct_name, "Shared");
+    assert_eq!(Move::VARIANT.index, 2);
+}
+
+#[test]
+fn names() {
+    assert_eq!(Note::from(Shared::default()).variant_name(), "Shared");
+    assert_eq!(Note::from(String::from("a")).variant_name(), "Label");
+    assert_eq!(Memo::NAME, "Memo");
+    assert_eq!(Log::from(Shared::default()).variant_name(), "Shared");
+    assert_eq!(Entry::NAME, "Entry");
+}
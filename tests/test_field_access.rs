@@ -0,0 +1,97 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(field_access)]
+#[derive(Debug, PartialEq)]
+enum Event {
+    Move { x: i32, r#type: String },
+    Say(String),
+    Quit,
+}
+
+#[extract_variant(style = "keep", no_impl, field_access, suffix(Payload))]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle { radius: f64 },
+    Rect { name: String, value: u8 },
+}
+
+#[test]
+fn struct_fields() {
+    let mut payload = Move {
+        x: 1,
+        r#type: String::from("walk"),
+    };
+    assert_eq!(payload.field("x").unwrap().downcast_ref::<i32>(), Some(&1));
+    assert!(payload.field("y").is_none());
+
+    *payload
+        .field_mut("x")
+        .unwrap()
+        .downcast_mut::<i32>()
+        .unwrap() = 5;
+    assert_eq!(payload.x, 5);
+
+    payload
+        .set_field("type", Box::new(String::from("run")))
+        .unwrap();
+    assert_eq!(payload.r#type, "run");
+    assert_eq!(
+        payload.set_field("x", Box::new(1u8)),
+        Err(EventFieldError::TypeMismatch {
+            field: "x",
+            expected: "i32",
+        })
+    );
+    assert_eq!(
+        payload.set_field("y", Box::new(1)),
+        Err(EventFieldError::UnknownField(String::from("y")))
+    );
+}
+
+#[test]
+fn enum_dispatch() {
+    let mut event = Event::Move(Move {
+        x: 1,
+        r#type: String::new(),
+    });
+    event.set_field("x", Box::new(3)).unwrap();
+    assert_eq!(event.field("x").unwrap().downcast_ref::<i32>(), Some(&3));
+
+    let mut event = Event::Say(Say(String::from("hi")));
+    assert!(event.field("0").is_none());
+    assert!(event.field_mut("x").is_none());
+    assert!(matches!(
+        Event::Quit(Quit).set_field("x", Box::new(3)),
+        Err(EventFieldError::UnknownField(_))
+    ));
+}
+
+#[test]
+fn keep_style() {
+    let mut shape = Shape::Rect {
+        name: String::new(),
+        value: 1,
+    };
+    shape.set_field("value", Box::new(2u8)).unwrap();
+    *shape
+        .field_mut("name")
+        .unwrap()
+        .downcast_mut::<String>()
+        .unwrap() += "box";
+    assert_eq!(
+        shape,
+        Shape::Rect {
+            name: String::from("box"),
+            value: 2,
+        }
+    );
+    assert!(shape.field("radius").is_none());
+    assert!(Shape::Circle { radius: 1.0 }.field("radius").is_some());
+
+    let payload = CirclePayload { radius: 1.5 };
+    assert_eq!(payload.field("radius").unwrap().downcast_ref(), Some(&1.5));
+    assert_eq!(
+        ShapeFieldError::UnknownField(String::from("side")).to_string(),
+        r#"`Shape` has no field "side" in the active variant"#
+    );
+}
@@ -0,0 +1,39 @@
+#![allow(dead_code)]
+
+use enum_macros::extract_variant;
+
+#[extract_variant(style = "keep", no_impl)]
+enum Event {
+    Move {
+        x: i32,
+        #[attribute(cfg(any()))]
+        enum_only: u8,
+        #[enum_attribute(cfg(any()))]
+        struct_only: u8,
+    },
+}
+
+#[extract_variant]
+enum Command {
+    Say {
+        #[enum_attribute(cfg(any()))]
+        #[attribute(allow(unused))]
+        text: String,
+    },
+}
+
+#[test]
+fn keep_style() {
+    let _ = Move {
+        x: 1,
+        struct_only: 2,
+    };
+    let _ = Event::Move { x: 1, enum_only: 3 };
+}
+
+#[test]
+fn wrap_style() {
+    let _ = Command::from(Say {
+        text: String::new(),
+    });
+}
@@ -0,0 +1,53 @@
+mod events {
+    use enum_macros::{extract_variant, EnableExtraParameters};
+
+    #[extract_variant]
+    pub enum Event {
+        Move { x: i32, y: i32 },
+        Say(String),
+    }
+
+    #[extract_variant(field_vis(pub(crate)), style = "keep", no_impl)]
+    #[derive(EnableExtraParameters)]
+    pub enum Shape {
+        Circle {
+            radius: f64,
+            #[attribute(vis = pub(super))]
+            center: (f64, f64),
+        },
+    }
+
+    #[extract_variant(field_vis = "inherit", module(payloads))]
+    pub(super) enum Request {
+        Get { id: u32 },
+    }
+}
+
+use events::{payloads, Circle, Event, Move, Say};
+
+#[test]
+fn inherit_enum_visibility() {
+    let event = Event::from(Move { x: 1, y: 2 });
+    let Event::Move(payload) = event else {
+        unreachable!()
+    };
+    assert_eq!(payload.x + payload.y, 3);
+    assert_eq!(Say(String::from("hi")).0, "hi");
+}
+
+#[test]
+fn explicit_visibility() {
+    let circle = Circle {
+        radius: 1.0,
+        center: (0.0, 0.0),
+    };
+    assert_eq!(circle.radius, 1.0);
+    assert_eq!(circle.center, (0.0, 0.0));
+}
+
+#[test]
+fn nested_module() {
+    let request = events::Request::from(payloads::Get { id: 4 });
+    let events::Request::Get(get) = request;
+    assert_eq!(get.id, 4);
+}
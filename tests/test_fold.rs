@@ -0,0 +1,52 @@
+use enum_macros::{extract_variant, variant_wrapper};
+
+#[extract_variant(fold)]
+enum Token {
+    Number(i64),
+    Word { text: String },
+    End,
+}
+
+struct Open;
+struct Close(u8);
+
+#[variant_wrapper(fold)]
+enum Paren {
+    Open,
+    Close(Close),
+}
+
+#[test]
+fn fold() {
+    let describe = |token: Token| {
+        token.fold(
+            |number| number.0.to_string(),
+            |word| word.text,
+            |_| String::from("<end>"),
+        )
+    };
+    assert_eq!(describe(Token::from(Number(3))), "3");
+    assert_eq!(describe(Token::from(End)), "<end>");
+}
+
+#[test]
+fn fold_ref_and_mut() {
+    let mut token = Token::from(Word {
+        text: String::from("a"),
+    });
+    token.fold_mut(|_| {}, |word| word.text.push('b'), |_| {});
+    assert_eq!(token.fold_ref(|_| 0, |word| word.text.len(), |_| 0), 2);
+}
+
+#[test]
+fn folder() {
+    let paren = Paren::from(Close(1));
+    let depth = paren
+        .folder_ref()
+        .close(|close| -i32::from(close.0))
+        .open(|_| 1)
+        .finish();
+    assert_eq!(depth, -1);
+    assert_eq!(paren.folder().open(|_| 1).close(|_| -1).finish(), -1);
+    assert!(Paren::from(Open).fold(|_| true, |_| false));
+}
@@ -0,0 +1,23 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(forward_attrs(allow), struct_attrs(repr(align(16)), derive(Default)))]
+#[allow(non_snake_case)]
+#[derive(Debug, PartialEq)]
+enum Vehicle {
+    Car { Speed: u32 },
+    Bike(u8),
+}
+
+#[test]
+fn struct_attrs() {
+    assert_eq!(core::mem::align_of::<Car>(), 16);
+    assert_eq!(core::mem::align_of::<Bike>(), 16);
+    assert_eq!(Car::default(), Car { Speed: 0 });
+}
+
+#[test]
+fn forwarded() {
+    let car = Car { Speed: 3 };
+    assert_eq!(car.Speed, 3);
+    assert_eq!(Vehicle::from(Bike(1)), Vehicle::Bike(Bike(1)));
+}
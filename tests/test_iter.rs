@@ -0,0 +1,72 @@
+use enum_macros::{extract_variant, variant_wrapper, EnableExtraParameters};
+
+#[extract_variant(iter(default), tag)]
+#[derive(Debug, PartialEq, EnableExtraParameters)]
+enum Mode {
+    Insert,
+    Normal {},
+    #[attribute(derive(Default))]
+    Visual {
+        lines: bool,
+    },
+}
+
+#[extract_variant(style = "keep", no_impl, iter(default))]
+#[derive(Debug, PartialEq)]
+enum Color {
+    Red,
+    Gray(u8),
+    Custom { r: u8, g: u8, b: u8 },
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Play;
+#[derive(Debug, Default, PartialEq)]
+struct Pause(u32);
+
+#[variant_wrapper(iter)]
+enum Player {
+    Play(Play),
+    Pause(Pause),
+}
+
+#[test]
+fn count_and_index() {
+    assert_eq!(Mode::VARIANT_COUNT, 3);
+    assert_eq!(Mode::Visual(Visual { lines: true }).variant_index(), 2);
+    assert_eq!(Player::VARIANT_COUNT, 2);
+    assert_eq!(Player::Pause(Pause(3)).variant_index(), 1);
+    assert_eq!(
+        ModeTag::ALL,
+        [ModeTag::Insert, ModeTag::Normal, ModeTag::Visual]
+    );
+}
+
+#[test]
+fn all_default() {
+    let modes: Vec<_> = Mode::all_default().collect();
+    assert_eq!(
+        modes,
+        [
+            Mode::Insert(Insert),
+            Mode::Normal(Normal {}),
+            Mode::Visual(Visual::default()),
+        ]
+    );
+    assert_eq!(Color::all_default().count(), 3);
+    assert_eq!(
+        Color::all_default().last(),
+        Some(Color::Custom { r: 0, g: 0, b: 0 })
+    );
+}
+
+#[test]
+fn cycling() {
+    assert_eq!(Mode::Insert(Insert).next(), Mode::Normal(Normal {}));
+    assert_eq!(
+        Mode::Insert(Insert).prev(),
+        Mode::Visual(Visual { lines: false })
+    );
+    assert_eq!(Color::Gray(7).next().next(), Color::Red);
+    assert_eq!(Color::Red.prev(), Color::Custom { r: 0, g: 0, b: 0 });
+}
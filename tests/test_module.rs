@@ -0,0 +1,57 @@
+use enum_macros::{extract_variant, EnableExtraParameters};
+
+// Would clash with the generated `Error` struct without `module`.
+#[allow(dead_code)]
+struct Error;
+
+#[extract_variant(module = payload, names)]
+#[derive(Debug, PartialEq, EnableExtraParameters)]
+enum Response {
+    Ok,
+    #[attribute(derive(Default))]
+    Error(String),
+}
+
+mod inner {
+    use enum_macros::{extract_variant, EnableExtraParameters};
+
+    #[extract_variant(module(requests), field_access)]
+    #[derive(Debug, PartialEq, EnableExtraParameters)]
+    pub(super) enum Request {
+        #[attribute(derive(Default))]
+        Get {
+            id: u32,
+        },
+        Ping,
+    }
+
+    pub(super) fn get(id: u32) -> Request {
+        let mut request = Request::Get(Default::default());
+        request.set_field("id", Box::new(id)).unwrap();
+        request
+    }
+}
+
+#[test]
+fn private_enum() {
+    assert_eq!(Response::from(payload::Ok), Response::Ok(payload::Ok));
+    assert_eq!(payload::Error::NAME, "Error");
+    let error = payload::Error::default();
+    assert_eq!(
+        payload::Error::try_from(Response::Error(error)),
+        Ok(payload::Error::default())
+    );
+}
+
+#[test]
+fn restricted_enum() {
+    use inner::{requests, Request};
+
+    let request = inner::get(3);
+    assert_eq!(request.field("id").unwrap().downcast_ref(), Some(&3u32));
+    assert_eq!(
+        requests::Get::try_from(request).map(|get| get.field("id").is_some()),
+        Ok(true)
+    );
+    assert_eq!(Request::from(requests::Ping), Request::Ping(requests::Ping));
+}
@@ -0,0 +1,48 @@
+#![allow(non_camel_case_types, dead_code)]
+
+use enum_macros::{extract_variant, EnableExtraParameters};
+
+#[extract_variant(name = "{Enum}{Variant}")]
+#[derive(Debug, PartialEq, EnableExtraParameters)]
+enum Command {
+    Move {
+        x: i32,
+    },
+    Quit,
+    #[attribute(rename = Shout)]
+    Say(String),
+}
+
+#[extract_variant(name = "{ENUM}_{variant}_payload", style = "keep", no_impl, views)]
+enum Event {
+    KeyPress(char),
+    Type { text: String },
+}
+
+#[test]
+fn enum_and_variant() {
+    assert_eq!(
+        Command::from(CommandMove { x: 1 }),
+        Command::Move(CommandMove { x: 1 })
+    );
+    assert_eq!(Command::from(CommandQuit), Command::Quit(CommandQuit));
+}
+
+#[test]
+fn rename() {
+    assert_eq!(
+        Command::from(Shout(String::from("hi"))),
+        Command::Say(Shout(String::from("hi")))
+    );
+}
+
+#[test]
+fn case_conversions() {
+    let _ = EVENT_key_press_payload('a');
+    let payload = EVENT_type_payload {
+        text: String::from("abc"),
+    };
+    let event = Event::Type { text: payload.text };
+    let view: EVENT_type_payloadRef<'_> = event.as_type_view().unwrap();
+    assert_eq!(view.text, "abc");
+}
@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use enum_macros::{extract_variant, variant_wrapper};
+
+#[extract_variant(names, tag, rename_all = "kebab-case")]
+#[derive(Debug, PartialEq)]
+enum Request {
+    GetUser {
+        id: u32,
+    },
+    ListUsers,
+    #[attribute(name = "delete")]
+    DeleteUser(u32),
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Start;
+#[derive(Debug, Default, PartialEq)]
+struct Stop(u8);
+
+#[variant_wrapper(names, from_str, rename_all = "SCREAMING_SNAKE_CASE")]
+#[derive(Debug, PartialEq)]
+enum Command {
+    Start,
+    Stop(Stop),
+}
+
+#[test]
+fn variant_name() {
+    assert_eq!(Request::from(GetUser { id: 1 }).variant_name(), "get-user");
+    assert_eq!(Request::from(ListUsers).variant_name(), "list-users");
+    assert_eq!(Request::from(DeleteUser(1)).variant_name(), "delete");
+    assert_eq!(Command::from(Stop(1)).variant_name(), "STOP");
+}
+
+#[test]
+fn name_consts() {
+    assert_eq!(GetUser::NAME, "get-user");
+    assert_eq!(DeleteUser::NAME, "delete");
+    assert_eq!(Start::NAME, "START");
+}
+
+#[test]
+fn tag() {
+    let request = Request::from(ListUsers);
+    assert_eq!(request.tag(), RequestTag::ListUsers);
+    assert_eq!(RequestTag::from(&request).to_string(), "list-users");
+    assert_eq!(RequestTag::from_str("delete"), Ok(RequestTag::DeleteUser));
+    assert!(RequestTag::from_str("DeleteUser").is_err());
+}
+
+#[test]
+fn from_str() {
+    assert_eq!("STOP".parse(), Ok(Command::Stop(Stop(0))));
+    assert_eq!(Command::from_str("START"), Ok(Command::Start(Start)));
+    assert_eq!(
+        Command::from_str("start").unwrap_err().to_string(),
+        r#"unknown `Command` variant name: "start""#
+    );
+}
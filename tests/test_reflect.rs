@@ -0,0 +1,55 @@
+#![allow(dead_code)]
+
+use enum_macros::{extract_variant, EnableExtraParameters};
+
+#[derive(EnableExtraParameters)]
+#[extract_variant(reflect, prefix(Cmd))]
+enum Command {
+    /// Moves the cursor.
+    #[attribute(derive(Debug))]
+    Move {
+        /// Horizontal offset.
+        dx: i32,
+        dy: Option<Vec<u8>>,
+    },
+    Say(String, &'static str),
+    Quit,
+}
+
+#[test]
+fn variants() {
+    assert_eq!(Command::VARIANTS.len(), 3);
+
+    let info = &Command::VARIANTS[0];
+    assert_eq!(info.name, "Move");
+    assert_eq!(info.index, 0);
+    assert_eq!(info.struct_name, "CmdMove");
+    assert_eq!(info.docs, ["Moves the cursor."]);
+    assert_eq!(info.attributes, ["derive(Debug)"]);
+    assert_eq!(
+        info.fields,
+        [
+            CommandFieldInfo {
+                name: "dx",
+                ty: "i32",
+                docs: &["Horizontal offset."],
+            },
+            CommandFieldInfo {
+                name: "dy",
+                ty: "Option<Vec<u8>>",
+                docs: &[],
+            },
+        ]
+    );
+
+    let info = &Command::VARIANTS[1];
+    assert_eq!(info.fields[0].name, "0");
+    assert_eq!(info.fields[1].ty, "&'static str");
+    assert!(Command::VARIANTS[2].fields.is_empty());
+}
+
+#[test]
+fn struct_variant() {
+    assert_eq!(CmdSay::VARIANT.index, 1);
+    assert_eq!(CmdQuit::VARIANT, &Command::VARIANTS[2]);
+}
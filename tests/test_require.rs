@@ -0,0 +1,34 @@
+use enum_macros::{extract_variant, variant_wrapper};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Job(String);
+#[derive(Debug, Clone, PartialEq)]
+struct Stop;
+
+#[variant_wrapper(require(Send, Sync, Clone, 'static))]
+#[derive(Debug, PartialEq)]
+enum Command {
+    Job(Job),
+    Stop(Stop),
+}
+
+#[extract_variant(require(Clone, ::core::fmt::Debug))]
+#[derive(Debug, Clone, PartialEq)]
+enum Reply {
+    Done,
+    Failed { reason: String },
+}
+
+#[test]
+fn require() {
+    assert_eq!(Command::from(Stop), Command::Stop(Stop));
+    assert_eq!(
+        Command::from(Job(String::new())),
+        Command::Job(Job(String::new()))
+    );
+    let reply = Reply::from(Failed {
+        reason: String::from("timeout"),
+    });
+    assert_eq!(reply.clone(), reply);
+    assert_eq!(Reply::from(Done), Reply::Done(Done));
+}
@@ -0,0 +1,53 @@
+use enum_macros::extract_variant;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timestamp(u64);
+
+#[extract_variant(shared(id: u64, ts: Timestamp))]
+#[derive(Debug, PartialEq)]
+enum Event {
+    Started,
+    Progress(u8),
+    Finished { code: i32 },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct External(u8);
+
+#[extract_variant(shared(id: u64), wrap_existing, common(code))]
+#[derive(Debug, PartialEq)]
+enum Mixed {
+    Local { code: i32 },
+    External(External),
+}
+
+#[test]
+fn shared_fields() {
+    let started = Started {
+        id: 1,
+        ts: Timestamp(10),
+    };
+    let progress = Progress {
+        id: 2,
+        ts: Timestamp(20),
+        _0: 50,
+    };
+    let mut event = Event::from(Finished {
+        id: 3,
+        ts: Timestamp(30),
+        code: 0,
+    });
+    assert_eq!(*event.id(), 3);
+    *event.ts_mut() = Timestamp(31);
+    assert_eq!(*event.ts(), Timestamp(31));
+    assert_eq!(*Event::from(started).id(), 1);
+    assert_eq!(Event::from(progress).ts(), &Timestamp(20));
+}
+
+#[test]
+fn shared_with_existing() {
+    let local = Mixed::from(Local { id: 4, code: 1 });
+    assert_eq!(local.id(), Some(&4));
+    assert_eq!(local.code(), Some(&1));
+    assert_eq!(Mixed::from(External(0)).id(), None);
+}
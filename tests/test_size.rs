@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+
+use std::mem::size_of;
+
+use enum_macros::{extract_variant, variant_wrapper, EnableExtraParameters};
+
+#[extract_variant(max_size = 16)]
+#[derive(Debug, EnableExtraParameters)]
+enum Message {
+    Ack(u64),
+    Move {
+        x: i32,
+        y: i32,
+    },
+    #[attribute(boxed)]
+    Blob {
+        data: [u8; 64],
+    },
+}
+
+#[derive(Debug)]
+struct Small(u8);
+#[derive(Debug)]
+struct Large([u64; 8]);
+
+#[variant_wrapper(size_report, box_above = 8)]
+#[derive(Debug)]
+enum Queued {
+    Small(Small),
+    Large(Large),
+}
+
+#[test]
+fn size_report() {
+    assert_eq!(
+        Message::SIZE_REPORT,
+        [("Ack", 8), ("Move", 8), ("Blob", size_of::<Box<Blob>>())]
+    );
+    assert_eq!(
+        Queued::SIZE_REPORT,
+        [("Small", 1), ("Large", size_of::<Box<Large>>())]
+    );
+}
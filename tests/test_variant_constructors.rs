@@ -0,0 +1,79 @@
+use enum_macros::{extract_variant, variant_wrapper, EnableExtraParameters};
+
+#[derive(Debug, PartialEq)]
+pub struct Shared(u8);
+
+#[extract_variant(constructors, wrap_existing)]
+#[derive(Debug, PartialEq, EnableExtraParameters)]
+enum Message {
+    Move {
+        x: i32,
+        y: i32,
+    },
+    Quit,
+    Shared(Shared),
+    #[attribute(rename = HttpRequest)]
+    Request(String, u16),
+}
+
+#[extract_variant(style = "keep", no_impl, constructors)]
+#[derive(Debug, PartialEq)]
+enum Color {
+    Rgb(u8, u8, u8),
+    Named { name: &'static str },
+    Black,
+}
+
+#[derive(Debug, PartialEq)]
+struct Ping;
+#[derive(Debug, PartialEq)]
+struct Pong(u32);
+
+#[variant_wrapper(constructors, box_above = 0)]
+#[derive(Debug, PartialEq)]
+enum Net {
+    Ping(Ping),
+    Pong(Pong),
+}
+
+#[test]
+fn wrap() {
+    assert_eq!(Message::r#move(1, 2), Message::Move(Move { x: 1, y: 2 }));
+    assert_eq!(Message::quit(), Message::Quit(Quit));
+    assert_eq!(Message::shared(Shared(3)), Message::Shared(Shared(3)));
+    assert_eq!(
+        Message::http_request(String::from("/"), 80),
+        Message::Request(HttpRequest(String::from("/"), 80))
+    );
+}
+
+#[test]
+fn keep() {
+    assert_eq!(Color::rgb(1, 2, 3), Color::Rgb(1, 2, 3));
+    assert_eq!(Color::named("red"), Color::Named { name: "red" });
+    assert_eq!(Color::black(), Color::Black);
+}
+
+#[test]
+fn wrapper() {
+    assert_eq!(Net::ping(Ping), Net::from(Ping));
+    assert_eq!(Pong::try_from(Net::pong(Pong(4))), Ok(Pong(4)));
+}
+
+#[extract_variant(constructors)]
+#[derive(Debug, PartialEq)]
+enum Sample {
+    Reading(u8, #[cfg(any())] u16, u32),
+}
+
+#[extract_variant(style = "keep", no_impl, constructors)]
+#[derive(Debug, PartialEq)]
+enum Probe {
+    Level(u8, #[cfg(any())] u16, u32),
+}
+
+#[test]
+fn disabled_field() {
+    assert_eq!(Sample::reading(1, 2), Sample::Reading(Reading(1, 2)));
+    assert_eq!(Probe::level(1, 2), Probe::Level(1, 2));
+}
@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+mod messages {
+    use enum_macros::{extract_variant, EnableExtraParameters};
+
+    #[extract_variant(style = "keep", no_impl)]
+    #[derive(Debug, Clone, PartialEq, EnableExtraParameters)]
+    pub enum Message {
+        Text(String),
+        #[attribute(vis = pub(crate), inherit_derive(Debug))]
+        Internal {
+            id: u32,
+        },
+        #[attribute(no_inherit_derive)]
+        Raw(Vec<u8>),
+    }
+
+    #[extract_variant]
+    pub enum Signal {
+        #[attribute(vis = pub(crate))]
+        Kill,
+    }
+
+    pub fn raw_len(raw: &Raw) -> usize {
+        raw.0.len()
+    }
+}
+
+use messages::{Internal, Raw, Text};
+
+#[test]
+fn inherited_derive() {
+    let text = Text(String::from("hi"));
+    assert_eq!(text.clone(), text);
+    assert_eq!(format!("{:?}", Internal { id: 3 }), "Internal { id: 3 }");
+    assert_eq!(messages::raw_len(&Raw(vec![1, 2])), 2);
+}
+
+#[test]
+fn visibility() {
+    let _ = messages::Signal::from(messages::Kill);
+    let message = messages::Message::Internal { id: 1 };
+    assert_eq!(message.clone(), message);
+}
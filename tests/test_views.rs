@@ -0,0 +1,35 @@
+#![allow(dead_code)]
+
+use enum_macros::extract_variant;
+
+#[extract_variant(style = "keep", no_impl, views)]
+enum Event {
+    Move { x: i32, y: i32 },
+    Say(String, u8),
+    Quit,
+}
+
+fn manhattan(view: MoveRef<'_>) -> i32 {
+    view.x.abs() + view.y.abs()
+}
+
+#[test]
+fn as_view() {
+    let event = Event::Move { x: 3, y: -4 };
+    assert_eq!(event.as_move_view().map(manhattan), Some(7));
+    assert!(event.as_say_view().is_none());
+    assert!(Event::Quit.as_move_view().is_none());
+
+    let event = Event::Say(String::from("hi"), 2);
+    let SayRef(text, volume) = event.as_say_view().unwrap();
+    assert_eq!((text.as_str(), *volume), ("hi", 2));
+}
+
+#[test]
+fn as_view_mut() {
+    let mut event = Event::Move { x: 1, y: 1 };
+    if let Some(view) = event.as_move_view_mut() {
+        *view.x += 1;
+    }
+    assert!(matches!(event, Event::Move { x: 2, y: 1 }));
+}
@@ -0,0 +1,84 @@
+use enum_macros::{extract_variant, variant_wrapper};
+
+#[extract_variant(visitor)]
+enum Shape {
+    Circle { radius: f64 },
+    Square(f64),
+    Empty,
+}
+
+struct Area;
+impl ShapeVisitor for Area {
+    type Output = f64;
+    fn visit_circle(&mut self, circle: &Circle) -> f64 {
+        3.0 * circle.radius * circle.radius
+    }
+    fn visit_square(&mut self, square: &Square) -> f64 {
+        square.0 * square.0
+    }
+    fn visit_empty(&mut self, _: &Empty) -> f64 {
+        0.0
+    }
+}
+
+struct Grow(f64);
+impl ShapeVisitorMut for Grow {
+    type Output = ();
+    fn visit_circle(&mut self, circle: &mut Circle) {
+        circle.radius *= self.0;
+    }
+    fn visit_square(&mut self, square: &mut Square) {
+        square.0 *= self.0;
+    }
+    fn visit_empty(&mut self, _: &mut Empty) {}
+}
+
+struct Name;
+impl ShapeVisitorOwned for Name {
+    type Output = &'static str;
+    fn visit_circle(&mut self, _: Circle) -> Self::Output {
+        "circle"
+    }
+    fn visit_square(&mut self, _: Square) -> Self::Output {
+        "square"
+    }
+    fn visit_empty(&mut self, _: Empty) -> Self::Output {
+        "empty"
+    }
+}
+
+struct Start;
+struct Stop;
+
+#[variant_wrapper(visitor(default))]
+enum Command {
+    Start,
+    Stop(Stop),
+}
+
+struct IsStop;
+impl CommandVisitor for IsStop {
+    type Output = bool;
+    fn visit_stop(&mut self, _: &Stop) -> bool {
+        true
+    }
+    fn visit_other(&mut self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn extract_variant_visitor() {
+    let mut shape = Shape::from(Square(2.0));
+    assert_eq!(shape.accept(&mut Area), 4.0);
+    shape.accept_mut(&mut Grow(2.0));
+    assert_eq!(shape.accept(&mut Area), 16.0);
+    assert_eq!(Shape::from(Circle { radius: 1.0 }).accept(&mut Area), 3.0);
+    assert_eq!(Shape::from(Empty).accept_owned(&mut Name), "empty");
+}
+
+#[test]
+fn variant_wrapper_visitor_default() {
+    assert!(Command::from(Stop).accept(&mut IsStop));
+    assert!(!Command::from(Start).accept(&mut IsStop));
+}
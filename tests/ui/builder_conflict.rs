@@ -0,0 +1,8 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(builder)]
+pub enum Step {
+    Compile { target: String, build: u32 },
+}
+
+fn main() {}
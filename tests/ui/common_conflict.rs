@@ -0,0 +1,9 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(common(tag), tag)]
+pub enum Packet {
+    Data { tag: u8, len: u16 },
+    Ack { tag: u8 },
+}
+
+fn main() {}
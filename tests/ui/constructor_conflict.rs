@@ -0,0 +1,9 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(constructors, tag)]
+pub enum Token {
+    Word(String),
+    Tag(u8),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use enum_macros::{extract_variant, EnableExtraParameters};
+
+#[extract_variant(constructors, tag)]
+#[derive(EnableExtraParameters)]
+pub enum Token {
+    Word(String),
+    #[attribute(rename = Tag)]
+    Label(u8),
+}
+
+fn main() {}
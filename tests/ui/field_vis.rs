@@ -0,0 +1,17 @@
+mod events {
+    use enum_macros::{extract_variant, EnableExtraParameters};
+
+    #[extract_variant]
+    #[derive(EnableExtraParameters)]
+    pub enum Event {
+        Move {
+            x: i32,
+            #[attribute(vis = pub(self))]
+            y: i32,
+        },
+    }
+}
+
+fn main() {
+    let _ = events::Move { x: 1, y: 2 };
+}
@@ -0,0 +1,9 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(fold)]
+pub enum Step {
+    Start(u8),
+    Finish(u8),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(fold)]
+enum MyEnum {
+    A,
+    B(u8),
+}
+
+fn main() {
+    let _ = MyEnum::from(A).folder().a(|_| 0).finish();
+}
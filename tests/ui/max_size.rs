@@ -0,0 +1,9 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(max_size = 16)]
+pub enum Message {
+    Ack(u64),
+    Blob { data: [u8; 64] },
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(name = "{Enum}{Kind}")]
+enum First {
+    A,
+}
+
+#[extract_variant(name = "{Enum}{Variant}", prefix(P))]
+enum Second {
+    A,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use std::rc::Rc;
+
+use enum_macros::variant_wrapper;
+
+pub struct Local(Rc<u8>);
+pub struct Remote(String);
+
+#[variant_wrapper(require(Send, 'static))]
+pub enum Task {
+    Remote(Remote),
+    Local(Local),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use enum_macros::extract_variant;
+
+#[extract_variant(visitor(default))]
+pub enum Event {
+    Other { x: u8 },
+    Key(u32),
+}
+
+fn main() {}